@@ -0,0 +1,6 @@
+// the value after `flag` on the command line, e.g. the path in `--replay path`
+pub fn flag_value<'a>(args : &'a [String], flag : &str) -> Option<&'a String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+}
@@ -0,0 +1,86 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    audio::{output::{init_output, Output}, AudioSink, Source, SourceHandle, WavFormat},
+    prelude::*,
+    utils::application_root_dir
+};
+use std::iter::Cycle;
+use std::vec::IntoIter;
+
+use crate::config::AudioConfig;
+
+const MUSIC_TRACKS : &[&str] = &[
+    "audio/music.wav"
+];
+
+pub struct Sounds {
+    pub bounce : SourceHandle,
+    pub wall : SourceHandle,
+    pub score : SourceHandle,
+    pub match_over : SourceHandle
+}
+
+// played back to back forever by the DjSystem
+pub struct Music {
+    pub music : Cycle<IntoIter<SourceHandle>>
+}
+
+// loads the sounds and starts the music, once; without an output device
+// there's no Output or AudioSink resource and everything that plays audio skips it
+pub fn initialize_audio(world : &mut World) {
+    if world.has_value::<Sounds>() {
+        return;
+    }
+    init_output(world);
+
+    let (sounds, music) = {
+        let loader = world.read_resource::<Loader>();
+        let storage = world.read_resource::<AssetStorage<Source>>();
+        let load = |file : &str| loader.load(file, WavFormat, (), &storage);
+
+        let sounds = Sounds {
+            bounce : load("audio/bounce.wav"),
+            wall : load("audio/wall.wav"),
+            score : load("audio/score.wav"),
+            match_over : load("audio/match_over.wav")
+        };
+        let tracks : Vec<SourceHandle> = MUSIC_TRACKS.iter().map(|file| load(file)).collect();
+        (sounds, Music { music : tracks.into_iter().cycle() })
+    };
+
+    world.insert(sounds);
+    world.insert(music);
+    apply_volume(world);
+}
+
+// pushes the music volume to the sink, effects read theirs as they play
+pub fn apply_volume(world : &mut World) {
+    let volume = world.read_resource::<AudioConfig>().music_gain();
+    if let Some(mut sink) = world.try_fetch_mut::<AudioSink>() {
+        sink.set_volume(volume);
+    }
+}
+
+// the shipped config/audio.ron stays as it is, the player's settings go to data/
+pub fn save_audio_config(world : &World) {
+    let config = world.read_resource::<AudioConfig>();
+    let saved = application_root_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|root| {
+            let defaults = AudioConfig::load_or_default(root.join("config").join("audio.ron"));
+            config.save_changes(&defaults, root.join("data").join("audio.ron")).map_err(|e| e.to_string())
+        });
+
+    if let Err(e) = saved {
+        eprintln!("could not save audio settings: {}", e);
+    }
+}
+
+pub fn play(sound : &SourceHandle, storage : &AssetStorage<Source>, output : Option<&Output>, volume : f32) {
+    if volume <= 0.0 {
+        return;
+    }
+    if let (Some(output), Some(sound)) = (output, storage.get(sound)) {
+        output.play_once(sound, volume);
+    }
+}
@@ -0,0 +1,99 @@
+use amethyst::{
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{UiButton, UiEvent, UiEventType, UiText}
+};
+
+use crate::audio::{apply_volume, save_audio_config};
+use crate::config::AudioConfig;
+use crate::menu::{create_button, delete_button, is_clicked, load_font};
+
+const VOLUMES : [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+// volumes and mutes, saved to data/audio.ron on the way out
+#[derive(Default)]
+pub struct AudioSettings {
+    effects : Option<UiButton>,
+    music : Option<UiButton>,
+    music_muted : Option<UiButton>,
+    muted : Option<UiButton>,
+    back : Option<UiButton>
+}
+
+impl AudioSettings {
+    fn refresh_labels(&self, world : &mut World) {
+        let config = world.read_resource::<AudioConfig>();
+        let mut ui_text = world.write_storage::<UiText>();
+
+        let labels = vec![
+            (&self.effects, format!("Effects: {}%", (config.effects_volume * 100.0).round())),
+            (&self.music, format!("Music: {}%", (config.music_volume * 100.0).round())),
+            (&self.music_muted, format!("Music: {}", if config.music_muted { "off" } else { "on" })),
+            (&self.muted, format!("Mute all: {}", if config.muted { "on" } else { "off" }))
+        ];
+        for (button, label) in labels {
+            if let Some(text) = button.as_ref().and_then(|button| ui_text.get_mut(button.text_entity)) {
+                text.text = label;
+            }
+        }
+    }
+}
+
+impl SimpleState for AudioSettings {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        let font = load_font(world);
+
+        self.effects = Some(create_button(world, font.clone(), "", 120.0));
+        self.music = Some(create_button(world, font.clone(), "", 60.0));
+        self.music_muted = Some(create_button(world, font.clone(), "", 0.0));
+        self.muted = Some(create_button(world, font.clone(), "", -60.0));
+        self.back = Some(create_button(world, font, "Back", -120.0));
+        self.refresh_labels(world);
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let buttons = [self.effects.take(), self.music.take(), self.music_muted.take(), self.muted.take(), self.back.take()];
+        for button in buttons.iter().flatten() {
+            delete_button(data.world, button);
+        }
+        save_audio_config(data.world);
+    }
+
+    fn handle_event(&mut self, data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Window(event) if is_key_down(event, VirtualKeyCode::Escape) => Trans::Pop,
+            StateEvent::Ui(UiEvent { event_type : UiEventType::Click, target }) => {
+                if is_clicked(&self.back, *target) {
+                    return Trans::Pop;
+                }
+
+                {
+                    let mut config = data.world.write_resource::<AudioConfig>();
+                    if is_clicked(&self.effects, *target) {
+                        config.effects_volume = next_volume(config.effects_volume);
+                    } else if is_clicked(&self.music, *target) {
+                        config.music_volume = next_volume(config.music_volume);
+                    } else if is_clicked(&self.music_muted, *target) {
+                        config.music_muted = !config.music_muted;
+                    } else if is_clicked(&self.muted, *target) {
+                        config.muted = !config.muted;
+                    }
+                }
+                apply_volume(data.world);
+                self.refresh_labels(data.world);
+                Trans::None
+            },
+            _ => Trans::None
+        }
+    }
+}
+
+// the next step up, wrapping to silence after full volume
+fn next_volume(volume : f32) -> f32 {
+    VOLUMES.iter()
+        .cloned()
+        .find(|step| *step > volume + 0.01)
+        .unwrap_or(VOLUMES[0])
+}
@@ -0,0 +1,350 @@
+// Ranks paddle controllers against each other: every entrant in
+// config/tournament.ron plays every other one, with the rules in
+// config/pong.ron. There's no window and no amethyst dispatcher, the sim is
+// stepped as fast as it goes and each controller is asked once a step, so a
+// tournament with the same seed plays out exactly the same again.
+//
+//     pong-tournament [--config <file>] [--seed <seed>] [--out <file>]
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use amethyst::utils::application_root_dir;
+use ron::de::from_reader;
+use serde::Deserialize;
+
+use pong::args::flag_value;
+use pong::config::{ControllerKind, PongConfig};
+use pong::controller::{self, NoInput, Observation, PaddleController};
+use pong::replay::dashed;
+use pong::sim::{Inputs, PongSim, Side, SimRng};
+use pong::stats::csv_text;
+
+const START_RATING : f32 = 1500.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct TournamentConfig {
+    entrants : Vec<Entrant>,
+    games_per_pair : u32, // split between the two sides, so better even
+    seed : u64,
+    max_seconds : f32,    // of play, a match still going then is a draw
+    elo_k : f32,          // rating points at stake in a match
+    results : String      // per-match results, relative to the app root
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        TournamentConfig {
+            entrants : Vec::new(),
+            games_per_pair : 2,
+            seed : 1,
+            max_seconds : 1800.0,
+            elo_k : 32.0,
+            results : "tournament/results.csv".to_string()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Entrant {
+    name : String,
+    controller : ControllerKind
+}
+
+// one played match, as it goes into the results file
+struct MatchResult {
+    game : usize,
+    seed : u64,
+    left : usize,  // entrant index
+    right : usize,
+    scores : Vec<u32>,
+    sets : Vec<u32>,
+    winner : Option<usize>,
+    seconds : f32
+}
+
+#[derive(Clone)]
+struct Standing {
+    rating : f32,
+    wins : u32,
+    losses : u32,
+    draws : u32
+}
+
+impl Standing {
+    fn played(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    fn win_rate(&self) -> f32 {
+        if self.played() == 0 {
+            0.0
+        } else {
+            (self.wins as f32 + self.draws as f32 * 0.5) / self.played() as f32
+        }
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("pong-tournament: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let root = application_root_dir()?;
+    let args : Vec<String> = std::env::args().skip(1).collect();
+
+    let config_path = flag_value(&args, "--config")
+        .map_or(root.join("config").join("tournament.ron"), PathBuf::from);
+    let mut tournament : TournamentConfig = from_reader(File::open(&config_path)?)
+        .map_err(|e| TournamentError(format!("could not parse {}: {}", config_path.display(), e)))?;
+    if let Some(seed) = flag_value(&args, "--seed") {
+        tournament.seed = seed.parse()
+            .map_err(|_| TournamentError(format!("--seed takes a number, got {:?}", seed)))?;
+    }
+    let results_path = flag_value(&args, "--out")
+        .map_or(root.join(&tournament.results), PathBuf::from);
+
+    // the two-player rules from the game's own config
+    let mut rules = PongConfig::load(root.join("config").join("pong.ron"))?;
+    rules.four_player.enabled = false;
+    rules.ai.side = None;
+    rules.rules.endless = false;
+    for entrant in tournament.entrants.iter() {
+        if let ControllerKind::Bot(name) = &entrant.controller {
+            controller::bot(name).ok_or_else(|| TournamentError(format!("{} plays a bot called {:?}, there's none", entrant.name, name)))?;
+        }
+    }
+    if tournament.entrants.len() < 2 {
+        return Err(Box::new(TournamentError("a tournament needs at least two entrants".to_string())));
+    }
+
+    let (standings, results) = play_all(&tournament, &rules, &root.join("replays"))?;
+    print_table(&tournament, &standings);
+    save_results(&results_path, &tournament, &results)?;
+    println!("Results written to {}", results_path.display());
+    Ok(())
+}
+
+// every pairing, games_per_pair times, with the seeds drawn from the tournament's
+fn play_all(tournament : &TournamentConfig, rules : &PongConfig, replays : &Path) -> Result<(Vec<Standing>, Vec<MatchResult>), Box<dyn Error>> {
+    let mut seeds = SimRng::new(tournament.seed);
+    let mut standings = vec![Standing { rating : START_RATING, wins : 0, losses : 0, draws : 0 }; tournament.entrants.len()];
+    let mut results = Vec::new();
+
+    for first in 0..tournament.entrants.len() {
+        for second in first + 1..tournament.entrants.len() {
+            for game in 0..tournament.games_per_pair {
+                // every other game they swap sides
+                let (left, right) = if game % 2 == 0 { (first, second) } else { (second, first) };
+                let seed = seeds.next_u64();
+
+                let result = play(tournament, rules, left, right, results.len() + 1, seed, replays)?;
+                rate(&mut standings, &result, tournament.elo_k);
+                println!("{:>4}. {} vs {}: {} (sets {}){}",
+                    result.game, tournament.entrants[left].name, tournament.entrants[right].name,
+                    dashed(&result.scores), dashed(&result.sets),
+                    result.winner.map_or(" draw".to_string(), |_| String::new()));
+                results.push(result);
+            }
+        }
+    }
+
+    Ok((standings, results))
+}
+
+fn play(tournament : &TournamentConfig, rules : &PongConfig, left : usize, right : usize, game : usize, seed : u64, replays : &Path) -> Result<MatchResult, Box<dyn Error>> {
+    let mut sim = PongSim::with_seed(rules.clone(), seed);
+    let dt = 1.0 / rules.tick_rate;
+
+    // the controllers roll their own dice, seeded off the match
+    let mut players : Vec<(Side, Box<dyn PaddleController>)> = Vec::new();
+    for (side, entrant) in [(Side::Left, left), (Side::Right, right)].iter() {
+        let entrant_seed = seed ^ (side.index() as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let controller = controller::create(&tournament.entrants[*entrant].controller, *side, rules, entrant_seed, replays)
+            .map_err(|e| TournamentError(format!("{}: {}", tournament.entrants[*entrant].name, e)))?;
+        players.push((*side, controller));
+    }
+
+    let mut inputs = Inputs::default();
+    while sim.winner().is_none() && sim.elapsed() < tournament.max_seconds {
+        for (side, controller) in players.iter_mut() {
+            let observation = Observation::new(&sim, *side, dt, &NoInput);
+            inputs.set(*side, controller.movement(&observation));
+        }
+        sim.step(&inputs, dt);
+    }
+
+    Ok(MatchResult {
+        game,
+        seed,
+        left,
+        right,
+        scores : sim.match_sides().iter().map(|side| sim.score(*side)).collect(),
+        sets : sim.match_sides().iter().map(|side| sim.sets(*side)).collect(),
+        winner : match sim.winner() {
+            Some(Side::Left) => Some(left),
+            Some(_) => Some(right),
+            None => None
+        },
+        seconds : sim.elapsed()
+    })
+}
+
+// Elo, the results in order: beating a higher rated entrant gains more
+fn rate(standings : &mut [Standing], result : &MatchResult, k : f32) {
+    let (left, right) = (result.left, result.right);
+    let expected = 1.0 / (1.0 + 10f32.powf((standings[right].rating - standings[left].rating) / 400.0));
+    let score = match result.winner {
+        Some(winner) if winner == left => 1.0,
+        Some(_) => 0.0,
+        None => 0.5
+    };
+
+    standings[left].rating += k * (score - expected);
+    standings[right].rating -= k * (score - expected);
+
+    match result.winner {
+        Some(winner) => {
+            let loser = if winner == left { right } else { left };
+            standings[winner].wins += 1;
+            standings[loser].losses += 1;
+        },
+        None => {
+            standings[left].draws += 1;
+            standings[right].draws += 1;
+        }
+    }
+}
+
+fn print_table(tournament : &TournamentConfig, standings : &[Standing]) {
+    let mut order : Vec<usize> = (0..standings.len()).collect();
+    order.sort_by(|a, b| standings[*b].rating.total_cmp(&standings[*a].rating)
+        .then(tournament.entrants[*a].name.cmp(&tournament.entrants[*b].name)));
+    let width = tournament.entrants.iter().map(|entrant| entrant.name.len()).max().unwrap_or(0).max(4);
+
+    println!();
+    println!("  # {:<width$}    Elo     W     L     D   Win %", "Name", width = width);
+    for (rank, index) in order.iter().enumerate() {
+        let standing = &standings[*index];
+        println!("{:>3} {:<width$} {:>6.0} {:>5} {:>5} {:>5} {:>6.1}",
+            rank + 1, tournament.entrants[*index].name, standing.rating,
+            standing.wins, standing.losses, standing.draws, standing.win_rate() * 100.0,
+            width = width);
+    }
+    println!();
+}
+
+fn save_results(path : &Path, tournament : &TournamentConfig, results : &[MatchResult]) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = File::create(path)?;
+
+    writeln!(file, "game,seed,left,right,left_score,right_score,left_sets,right_sets,winner,seconds")?;
+    for result in results.iter() {
+        let name = |index : usize| csv_text(&tournament.entrants[index].name);
+        writeln!(file, "{},{},{},{},{},{},{},{},{},{:.2}",
+            result.game, result.seed, name(result.left), name(result.right),
+            result.scores[0], result.scores[1], result.sets[0], result.sets[1],
+            result.winner.map_or(String::new(), name), result.seconds)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+struct TournamentError(String);
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for TournamentError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pong::config::Difficulty;
+
+    fn standing(rating : f32) -> Standing {
+        Standing { rating, wins : 0, losses : 0, draws : 0 }
+    }
+
+    fn result(winner : Option<usize>) -> MatchResult {
+        MatchResult { game : 1, seed : 0, left : 0, right : 1, scores : vec![0, 0], sets : vec![0, 0], winner, seconds : 0.0 }
+    }
+
+    #[test]
+    fn even_ratings_move_by_half_of_k() {
+        let mut standings = vec![standing(START_RATING), standing(START_RATING)];
+        rate(&mut standings, &result(Some(0)), 32.0);
+        assert!((standings[0].rating - (START_RATING + 16.0)).abs() < 1e-3);
+        assert!((standings[1].rating - (START_RATING - 16.0)).abs() < 1e-3);
+        assert_eq!((standings[0].wins, standings[1].losses), (1, 1));
+    }
+
+    #[test]
+    fn points_won_are_points_lost() {
+        for &(left, right) in [(1500.0, 1500.0), (1700.0, 1400.0), (1200.0, 1650.0)].iter() {
+            for winner in [Some(0), Some(1), None].iter() {
+                let mut standings = vec![standing(left), standing(right)];
+                rate(&mut standings, &result(*winner), 32.0);
+                assert!((standings[0].rating + standings[1].rating - (left + right)).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn upsets_move_ratings_by_the_expected_score() {
+        // 400 points apart the favourite is expected to score 10/11
+        let expected = 10.0 / 11.0;
+
+        let mut standings = vec![standing(1900.0), standing(1500.0)];
+        rate(&mut standings, &result(Some(1)), 32.0);
+        assert!((standings[0].rating - (1900.0 - 32.0 * expected)).abs() < 1e-3);
+
+        let mut standings = vec![standing(1900.0), standing(1500.0)];
+        rate(&mut standings, &result(None), 32.0);
+        assert!((standings[0].rating - (1900.0 + 32.0 * (0.5 - expected))).abs() < 1e-3);
+        assert_eq!((standings[0].draws, standings[1].draws), (1, 1));
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_standings() {
+        let tournament = TournamentConfig {
+            entrants : vec![
+                Entrant { name : "easy".to_string(), controller : ControllerKind::Ai(Difficulty::Easy) },
+                Entrant { name : "hard".to_string(), controller : ControllerKind::Ai(Difficulty::Hard) },
+                Entrant { name : "follower".to_string(), controller : ControllerKind::Bot("follower".to_string()) }
+            ],
+            seed : 7,
+            max_seconds : 60.0,
+            ..TournamentConfig::default()
+        };
+        let mut rules = PongConfig::default();
+        rules.rules.points_to_win = 3;
+        let replays = Path::new("replays");
+
+        let summary = |(standings, results) : (Vec<Standing>, Vec<MatchResult>)| {
+            let standings : Vec<(f32, u32, u32, u32)> = standings.iter()
+                .map(|standing| (standing.rating, standing.wins, standing.losses, standing.draws))
+                .collect();
+            let results : Vec<(u64, Vec<u32>, Option<usize>)> = results.into_iter()
+                .map(|result| (result.seed, result.scores, result.winner))
+                .collect();
+            (standings, results)
+        };
+        let first = summary(play_all(&tournament, &rules, replays).unwrap());
+        let second = summary(play_all(&tournament, &rules, replays).unwrap());
+        assert_eq!(first, second);
+        assert_eq!(first.1.len(), 6);
+    }
+}
@@ -0,0 +1,727 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::Path;
+
+use ron::de::from_reader;
+use ron::ser::to_string;
+use serde::{Deserialize, Serialize};
+
+use crate::controller;
+use crate::level::Level;
+use crate::sim::{Side, SIDES};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ArenaConfig {
+    pub width : f32,
+    pub height : f32
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        ArenaConfig {
+            width : 100.0,
+            height : 100.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BallConfig {
+    pub velocity_x : f32,
+    pub velocity_y : f32,
+    pub radius : f32,
+    pub speed_up : f32, // speed multiplier applied on every paddle hit
+    pub max_speed : f32 // cap for the speed-up, in units per second
+}
+
+impl BallConfig {
+    pub fn base_speed(&self) -> f32 {
+        (self.velocity_x * self.velocity_x + self.velocity_y * self.velocity_y).sqrt()
+    }
+}
+
+impl Default for BallConfig {
+    fn default() -> Self {
+        BallConfig {
+            velocity_x : 75.0,
+            velocity_y : 50.0,
+            radius : 2.0,
+            speed_up : 1.0,
+            max_speed : 150.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PaddleConfig {
+    pub width : f32,
+    pub height : f32,
+    pub speed : f32,            // units per second at full input
+    pub max_bounce_angle : f32, // degrees off the horizontal for a hit on the paddle's very edge
+    pub english : f32           // fraction of the paddle's vertical velocity given to the ball
+}
+
+impl Default for PaddleConfig {
+    fn default() -> Self {
+        PaddleConfig {
+            width : 4.0,
+            height : 16.0,
+            speed : 72.0,
+            max_bounce_angle : 60.0,
+            english : 0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct AiSettings {
+    pub reaction_delay : f32,   // seconds between two looks at the ball
+    pub max_speed : f32,        // fraction of the paddle speed, 0.0 - 1.0
+    pub predict_bounces : bool, // follow the ball off the top and bottom walls
+    pub error : f32             // max distance the aim is off by, in arena units
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom(AiSettings)
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom(_) => "Custom"
+        }
+    }
+
+    pub fn settings(&self) -> AiSettings {
+        match self {
+            Difficulty::Easy => AiSettings {
+                reaction_delay : 0.35,
+                max_speed : 0.6,
+                predict_bounces : false,
+                error : 8.0
+            },
+            Difficulty::Normal => AiSettings {
+                reaction_delay : 0.2,
+                max_speed : 0.85,
+                predict_bounces : true,
+                error : 4.0
+            },
+            Difficulty::Hard => AiSettings {
+                reaction_delay : 0.08,
+                max_speed : 1.0,
+                predict_bounces : true,
+                error : 1.0
+            },
+            Difficulty::Custom(settings) => *settings
+        }
+    }
+}
+
+// names the match history and leaderboard know the local players by
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PlayerNames {
+    pub left : String,
+    pub right : String,
+    pub top : String, // Top and Bottom only play in four-player matches
+    pub bottom : String
+}
+
+impl Default for PlayerNames {
+    fn default() -> Self {
+        PlayerNames {
+            left : "Player 1".to_string(),
+            right : "Player 2".to_string(),
+            top : "Player 3".to_string(),
+            bottom : "Player 4".to_string()
+        }
+    }
+}
+
+impl PlayerNames {
+    pub fn name(&self, side : Side) -> &str {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+            Side::Top => &self.top,
+            Side::Bottom => &self.bottom
+        }
+    }
+}
+
+// who moves each paddle; a side left at None goes by the game mode, the
+// computer for the AI sides and the keyboard for the rest
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ControllerConfig {
+    pub left : Option<ControllerKind>,
+    pub right : Option<ControllerKind>,
+    pub top : Option<ControllerKind>,
+    pub bottom : Option<ControllerKind>
+}
+
+impl ControllerConfig {
+    pub fn get(&self, side : Side) -> Option<&ControllerKind> {
+        match side {
+            Side::Left => self.left.as_ref(),
+            Side::Right => self.right.as_ref(),
+            Side::Top => self.top.as_ref(),
+            Side::Bottom => self.bottom.as_ref()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum ControllerKind {
+    Keyboard,       // the side's axis in bindings.ron
+    Gamepad(u32),   // the left stick of the controller with this id
+    Ai(Difficulty),
+    Replay(String), // file in replays/, the side plays what it did in that match
+    Bot(String)     // by the name it's registered under in controller::bot
+}
+
+impl ControllerKind {
+    // for the match history and the stats
+    pub fn describe(&self) -> Option<String> {
+        match self {
+            ControllerKind::Keyboard | ControllerKind::Gamepad(_) => None,
+            ControllerKind::Ai(difficulty) => Some(format!("AI ({})", difficulty.name())),
+            ControllerKind::Replay(file) => Some(format!("Replay ({})", file)),
+            ControllerKind::Bot(name) => Some(format!("Bot ({})", name))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AiConfig {
+    pub side : Option<Side>, // paddle the computer plays in 1P modes, Right when None
+    pub difficulty : Difficulty
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        AiConfig {
+            side : None,
+            difficulty : Difficulty::Normal
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MatchRules {
+    pub points_to_win : u32, // points needed to take a set
+    pub win_by_two : bool,
+    pub best_of_sets : u32,  // odd, the match ends once a side has won the majority
+    pub endless : bool       // points are still counted but nobody ever wins
+}
+
+impl MatchRules {
+    pub fn sets_to_win(&self) -> u32 {
+        self.best_of_sets / 2 + 1
+    }
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            points_to_win : 11,
+            win_by_two : true,
+            best_of_sets : 1,
+            endless : false
+        }
+    }
+}
+
+// Left and Right against Top and Bottom, each defending its own edge of the
+// arena; a side that concedes its last life is out and its edge becomes a wall
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FourPlayerConfig {
+    pub enabled : bool,
+    pub lives : u32,
+    pub ai_sides : Vec<Side> // played by the computer, the rest by the keyboard
+}
+
+impl Default for FourPlayerConfig {
+    fn default() -> Self {
+        FourPlayerConfig {
+            enabled : false,
+            lives : 5,
+            ai_sides : vec![Side::Top, Side::Bottom]
+        }
+    }
+}
+
+// after every point the ball waits at the center, then leaves toward the
+// player who conceded at a random angle of up to max_angle either side
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    pub delay : f32,    // seconds
+    pub max_angle : f32 // degrees off straight across
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        ServeConfig {
+            delay : 3.0,
+            max_angle : 30.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MultiballConfig {
+    pub enabled : bool,
+    pub max_balls : u32,
+    pub spawn_interval : f32,   // seconds between extra balls, 0 for none
+    pub spawn_every_hits : u32, // rally hits between extra balls, 0 for none
+    pub goals_per_round : u32   // goals before the round is served again, 0 to play until no ball is left
+}
+
+impl Default for MultiballConfig {
+    fn default() -> Self {
+        MultiballConfig {
+            enabled : false,
+            max_balls : 3,
+            spawn_interval : 10.0,
+            spawn_every_hits : 6,
+            goals_per_round : 3
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PowerUpKind {
+    Grow,            // magnitude: paddle height factor
+    Shrink,          // magnitude: paddle height factor
+    SlowBall,        // magnitude: ball speed factor
+    Sticky,          // magnitude: seconds the ball is held before it leaves the paddle
+    Shield,          // a wall behind the paddle, magnitude unused
+    ReverseControls  // magnitude unused
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EffectTarget {
+    Collector,
+    Opponent
+}
+
+// what collecting an effect that is already active does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Stacking {
+    Refresh,    // restart the running effect's duration
+    Extend,     // add the duration to the running effect
+    Stack(u32), // run side by side, magnitudes compound, up to this many at once
+    Ignore      // keep the running effect as it is
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PowerUpEntry {
+    pub kind : PowerUpKind,
+    pub weight : u32,  // relative chance to be picked on a spawn
+    pub duration : f32,
+    pub magnitude : f32,
+    pub target : EffectTarget,
+    pub stacking : Stacking
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PowerUpConfig {
+    pub enabled : bool,
+    pub spawn_interval : f32, // seconds between pickups appearing
+    pub max_pickups : u32,    // pickups waiting in the arena at once
+    pub radius : f32,
+    pub table : Vec<PowerUpEntry>
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        let entry = |kind, weight, duration, magnitude, target, stacking| PowerUpEntry {
+            kind, weight, duration, magnitude, target, stacking
+        };
+
+        PowerUpConfig {
+            enabled : false,
+            spawn_interval : 8.0,
+            max_pickups : 2,
+            radius : 3.0,
+            table : vec![
+                entry(PowerUpKind::Grow, 3, 10.0, 1.5, EffectTarget::Collector, Stacking::Stack(2)),
+                entry(PowerUpKind::Shrink, 3, 8.0, 0.6, EffectTarget::Opponent, Stacking::Refresh),
+                entry(PowerUpKind::SlowBall, 2, 5.0, 0.6, EffectTarget::Collector, Stacking::Refresh),
+                entry(PowerUpKind::Sticky, 2, 10.0, 0.6, EffectTarget::Collector, Stacking::Extend),
+                entry(PowerUpKind::Shield, 1, 6.0, 1.0, EffectTarget::Collector, Stacking::Extend),
+                entry(PowerUpKind::ReverseControls, 1, 5.0, 1.0, EffectTarget::Opponent, Stacking::Ignore)
+            ]
+        }
+    }
+}
+
+// volumes are 0.0 - 1.0; the defaults are kept out of pong.ron in
+// config/audio.ron, what the player changes in the menu goes to
+// data/audio.ron and is read on top of them
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    pub effects_volume : f32,
+    pub music_volume : f32,
+    pub music_muted : bool,
+    pub muted : bool // everything, music included
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            effects_volume : 0.8,
+            music_volume : 0.4,
+            music_muted : false,
+            muted : false
+        }
+    }
+}
+
+impl AudioConfig {
+    // sound is never worth refusing to start over, a missing or broken file means the defaults
+    pub fn load_or_default<P : AsRef<Path>>(path : P) -> AudioConfig {
+        File::open(path).ok()
+            .and_then(|file| from_reader::<_, AudioConfig>(file).ok())
+            .unwrap_or_default()
+    }
+
+    // the defaults with whatever the player changed on top
+    pub fn load_with_changes<P : AsRef<Path>, Q : AsRef<Path>>(defaults : P, changes : Q) -> AudioConfig {
+        let mut config = AudioConfig::load_or_default(defaults);
+        if let Some(changes) = File::open(changes).ok().and_then(|file| from_reader::<_, AudioChanges>(file).ok()) {
+            changes.apply(&mut config);
+        }
+        config
+    }
+
+    // only what differs from the defaults, so a default changed later
+    // still reaches everything the player never touched
+    pub fn save_changes<P : AsRef<Path>>(&self, defaults : &AudioConfig, path : P) -> Result<(), ConfigError> {
+        let changed = |value : f32, default : f32| Some(value).filter(|value| *value != default);
+        let toggled = |value : bool, default : bool| Some(value).filter(|value| *value != default);
+        let changes = AudioChanges {
+            effects_volume : changed(self.effects_volume, defaults.effects_volume),
+            music_volume : changed(self.music_volume, defaults.music_volume),
+            music_muted : toggled(self.music_muted, defaults.music_muted),
+            muted : toggled(self.muted, defaults.muted)
+        };
+
+        let text = to_string(&changes)
+            .map_err(|e| ConfigError::Parse(e.to_string()))?;
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn effects_gain(&self) -> f32 {
+        if self.muted { 0.0 } else { self.effects_volume }
+    }
+
+    pub fn music_gain(&self) -> f32 {
+        if self.muted || self.music_muted { 0.0 } else { self.music_volume }
+    }
+}
+
+// the player's audio settings in data/audio.ron, None keeps the default
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+struct AudioChanges {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effects_volume : Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    music_volume : Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    music_muted : Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    muted : Option<bool>
+}
+
+impl AudioChanges {
+    fn apply(&self, config : &mut AudioConfig) {
+        config.effects_volume = self.effects_volume.unwrap_or(config.effects_volume);
+        config.music_volume = self.music_volume.unwrap_or(config.music_volume);
+        config.music_muted = self.music_muted.unwrap_or(config.music_muted);
+        config.muted = self.muted.unwrap_or(config.muted);
+    }
+}
+
+// online play, the host and join addresses come from the command line
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NetConfig {
+    pub port : u16,             // the host listens here
+    pub input_delay : u32,      // sim steps between pressing a key and the paddle moving
+    pub max_rollback : u32,     // steps the game may run ahead of the other peer's inputs
+    pub checksum_interval : u32, // steps between desync checks
+    pub timeout : f32           // seconds without a packet, or without new inputs, before the peer counts as gone
+}
+
+impl Default for NetConfig {
+    fn default() -> Self {
+        NetConfig {
+            port : 7777,
+            input_delay : 2,
+            max_rollback : 8,
+            checksum_interval : 60,
+            timeout : 5.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PongConfig {
+    pub tick_rate : f32, // sim steps per second, independent of the frame rate
+    pub arena : ArenaConfig,
+    pub ball : BallConfig,
+    pub paddles : PaddleConfig,
+    pub players : PlayerNames,
+    pub ai : AiConfig,
+    pub controllers : ControllerConfig,
+    pub rules : MatchRules,
+    pub four_player : FourPlayerConfig,
+    pub serve : ServeConfig,
+    pub multiball : MultiballConfig,
+    pub power_ups : PowerUpConfig,
+    pub net : NetConfig,
+    pub level : Level // picked from config/levels/ in the settings, empty here
+}
+
+impl Default for PongConfig {
+    fn default() -> Self {
+        PongConfig {
+            tick_rate : 120.0,
+            arena : ArenaConfig::default(),
+            ball : BallConfig::default(),
+            paddles : PaddleConfig::default(),
+            players : PlayerNames::default(),
+            ai : AiConfig::default(),
+            controllers : ControllerConfig::default(),
+            rules : MatchRules::default(),
+            four_player : FourPlayerConfig::default(),
+            serve : ServeConfig::default(),
+            multiball : MultiballConfig::default(),
+            power_ups : PowerUpConfig::default(),
+            net : NetConfig::default(),
+            level : Level::default()
+        }
+    }
+}
+
+impl PongConfig {
+    pub fn load<P : AsRef<Path>>(path : P) -> Result<PongConfig, ConfigError> {
+        let file = File::open(path)?;
+        let config : PongConfig = from_reader(file)
+            .map_err(|e| ConfigError::Parse(e.to_string()))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    // the sides the computer plays, none in a two-player match
+    pub fn ai_sides(&self) -> Vec<Side> {
+        if self.four_player.enabled {
+            self.four_player.ai_sides.clone()
+        } else {
+            self.ai.side.into_iter().collect()
+        }
+    }
+
+    // what moves the side's paddle, by the controllers section or else the game mode
+    pub fn controller(&self, side : Side) -> ControllerKind {
+        match self.controllers.get(side) {
+            Some(kind) => kind.clone(),
+            None if self.ai_sides().contains(&side) => ControllerKind::Ai(self.ai.difficulty),
+            None => ControllerKind::Keyboard
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let arena = &self.arena;
+        let ball = &self.ball;
+        let paddles = &self.paddles;
+
+        check(self.tick_rate > 0.0,
+            format!("tick_rate must be positive, got {}", self.tick_rate))?;
+        check(arena.width > 0.0 && arena.height > 0.0,
+            format!("arena must have a positive size, got {} x {}", arena.width, arena.height))?;
+        check(paddles.width > 0.0 && paddles.height > 0.0,
+            format!("paddles must have a positive size, got {} x {}", paddles.width, paddles.height))?;
+        check(paddles.height <= arena.height,
+            format!("paddle height ({}) is taller than the arena ({})", paddles.height, arena.height))?;
+        check(paddles.width * 2.0 < arena.width,
+            format!("two paddles of width {} do not fit in an arena {} wide", paddles.width, arena.width))?;
+        check(paddles.speed.is_finite() && paddles.speed > 0.0,
+            format!("paddle speed must be positive, got {}", paddles.speed))?;
+        check(paddles.max_bounce_angle > 0.0 && paddles.max_bounce_angle < 90.0,
+            format!("paddle max_bounce_angle must be between 0 and 90 degrees, got {}", paddles.max_bounce_angle))?;
+        check(paddles.english >= 0.0,
+            format!("paddle english must not be negative, got {}", paddles.english))?;
+        check(ball.radius > 0.0 && ball.radius * 2.0 < arena.height,
+            format!("ball radius ({}) does not fit in the arena", ball.radius))?;
+        check(ball.velocity_x.is_finite() && ball.velocity_y.is_finite(),
+            format!("ball velocity must be finite, got ({}, {})", ball.velocity_x, ball.velocity_y))?;
+        check(ball.velocity_x != 0.0,
+            "ball velocity_x must not be 0, the ball would never reach a goal".to_string())?;
+        check(ball.speed_up >= 1.0,
+            format!("ball speed_up must be at least 1.0, got {}", ball.speed_up))?;
+        check(ball.max_speed >= ball.base_speed(),
+            format!("ball max_speed ({}) is below its starting speed ({})", ball.max_speed, ball.base_speed()))?;
+
+        let players = &self.players;
+        check([&players.left, &players.right, &players.top, &players.bottom].iter().all(|name| !name.trim().is_empty()),
+            "players need names for the match history".to_string())?;
+
+        let ai = self.ai.difficulty.settings();
+        check(ai.reaction_delay >= 0.0,
+            format!("ai reaction_delay must not be negative, got {}", ai.reaction_delay))?;
+        check(ai.max_speed > 0.0 && ai.max_speed <= 1.0,
+            format!("ai max_speed is a fraction of the paddle speed and must be in (0, 1], got {}", ai.max_speed))?;
+        check(ai.error >= 0.0,
+            format!("ai error must not be negative, got {}", ai.error))?;
+
+        let rules = &self.rules;
+        check(rules.points_to_win > 0,
+            "rules points_to_win must be at least 1".to_string())?;
+        check(rules.best_of_sets % 2 == 1,
+            format!("rules best_of_sets must be odd so a set majority exists, got {}", rules.best_of_sets))?;
+
+        let four_player = &self.four_player;
+        check(four_player.lives > 0,
+            "four_player lives must be at least 1".to_string())?;
+        if four_player.enabled {
+            check(paddles.height <= arena.width && paddles.width * 2.0 < arena.height,
+                format!("flat paddles of {} x {} do not fit in an arena {} x {}", paddles.height, paddles.width, arena.width, arena.height))?;
+            check(ball.radius * 2.0 < arena.width,
+                format!("ball radius ({}) does not fit in the arena", ball.radius))?;
+        }
+
+        let serve = &self.serve;
+        check(serve.delay >= 0.0,
+            format!("serve delay must not be negative, got {}", serve.delay))?;
+        check(serve.max_angle >= 0.0 && serve.max_angle < 90.0,
+            format!("serve max_angle must be between 0 and 90 degrees, got {}", serve.max_angle))?;
+
+        let multiball = &self.multiball;
+        check(multiball.max_balls >= 1,
+            "multiball max_balls must be at least 1".to_string())?;
+        check(multiball.spawn_interval >= 0.0,
+            format!("multiball spawn_interval must not be negative, got {}", multiball.spawn_interval))?;
+
+        // checked even when off, Settings can turn them on later
+        let power_ups = &self.power_ups;
+        check(power_ups.spawn_interval > 0.0,
+            format!("power_ups spawn_interval must be positive, got {}", power_ups.spawn_interval))?;
+        check(power_ups.radius > 0.0,
+            format!("power_ups radius must be positive, got {}", power_ups.radius))?;
+        check(power_ups.table.iter().map(|entry| entry.weight).sum::<u32>() > 0,
+            "power_ups table needs at least one entry with a weight above 0".to_string())?;
+        for entry in power_ups.table.iter() {
+            check(entry.duration > 0.0 && entry.magnitude > 0.0,
+                format!("power up {:?} needs a positive duration and magnitude", entry.kind))?;
+        }
+
+        let net = &self.net;
+        check(net.max_rollback > 0,
+            "net max_rollback must be at least 1".to_string())?;
+        check(net.checksum_interval > 0,
+            "net checksum_interval must be at least 1".to_string())?;
+        check(net.timeout > 0.0,
+            format!("net timeout must be positive, got {}", net.timeout))?;
+
+        for side in SIDES.iter() {
+            if let Some(ControllerKind::Bot(name)) = self.controllers.get(*side) {
+                check(controller::bot(name).is_some(),
+                    format!("no bot called {:?} for the {:?} paddle", name, side))?;
+            }
+        }
+
+        self.level.validate(self).map_err(ConfigError::Invalid)?;
+
+        Ok(())
+    }
+}
+
+fn check(condition : bool, message : String) -> Result<(), ConfigError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(message))
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String)
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "could not read pong config: {}", e),
+            ConfigError::Parse(e) => write!(f, "could not parse pong config: {}", e),
+            ConfigError::Invalid(e) => write!(f, "invalid pong config: {}", e)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e : std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_changes_sit_on_top_of_the_defaults() {
+        let dir = std::env::temp_dir().join(format!("pong-audio-{}", std::process::id()));
+        let defaults_path = dir.join("defaults.ron");
+        let changes_path = dir.join("data").join("audio.ron");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&defaults_path, "(effects_volume : 0.5, music_volume : 0.3)").unwrap();
+
+        let defaults = AudioConfig::load_or_default(&defaults_path);
+        let mut config = defaults.clone();
+        config.music_volume = 1.0;
+        config.muted = true;
+        config.save_changes(&defaults, &changes_path).unwrap();
+
+        // a default changed after the save still counts where the player left it alone
+        fs::write(&defaults_path, "(effects_volume : 0.25, music_volume : 0.3)").unwrap();
+        let loaded = AudioConfig::load_with_changes(&defaults_path, &changes_path);
+        assert_eq!(loaded.effects_volume, 0.25);
+        assert_eq!(loaded.music_volume, 1.0);
+        assert!(loaded.muted);
+        assert!(!loaded.music_muted);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
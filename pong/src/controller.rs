@@ -0,0 +1,271 @@
+use std::path::Path;
+
+use crate::config::{AiSettings, ControllerKind, PongConfig};
+use crate::replay::Replay;
+use crate::sim::{BallState, PaddleState, PongSim, Side, SimRng, SIDES};
+
+// Whatever moves a paddle: a player on the keyboard or a gamepad, the
+// computer, a recording or a bot. Each gets what it could see on the screen
+// and answers with an axis value, the way a keyboard axis would; PaddleSystem
+// asks every frame in the game, the tournament runner every sim step.
+pub trait PaddleController : Send + Sync {
+    // -1.0 - 1.0 along the paddle's lane, toward its high end when positive
+    fn movement(&mut self, observation : &Observation<'_>) -> f32;
+}
+
+// the axis values bound in bindings.ron, none when nobody is at the keys
+pub trait AxisInput {
+    fn axis_value(&self, axis : &str) -> Option<f32>;
+}
+
+pub struct NoInput;
+
+impl AxisInput for NoInput {
+    fn axis_value(&self, _axis : &str) -> Option<f32> {
+        None
+    }
+}
+
+pub struct Observation<'a> {
+    pub side : Side,
+    pub dt : f32,   // seconds since the controller was last asked
+    pub step : u64, // sim steps so far
+    pub config : &'a PongConfig,
+    pub balls : &'a [BallState],     // positions and velocities
+    pub paddles : &'a [PaddleState], // its own and the opponents', a side knocked out has none
+    pub scores : [u32; 4],           // by Side::index, lives left in four-player
+    pub sets : [u32; 4],
+    pub input : &'a dyn AxisInput
+}
+
+impl<'a> Observation<'a> {
+    pub fn new(sim : &'a PongSim, side : Side, dt : f32, input : &'a dyn AxisInput) -> Observation<'a> {
+        let mut scores = [0; 4];
+        let mut sets = [0; 4];
+        for other in SIDES.iter() {
+            scores[other.index()] = if sim.config().four_player.enabled { sim.lives(*other) } else { sim.score(*other) };
+            sets[other.index()] = sim.sets(*other);
+        }
+
+        Observation {
+            side,
+            dt,
+            step : sim.steps(),
+            config : sim.config(),
+            balls : sim.balls(),
+            paddles : sim.paddles(),
+            scores,
+            sets,
+            input
+        }
+    }
+
+    pub fn own(&self) -> Option<&PaddleState> {
+        self.paddles.iter().find(|paddle| paddle.side == self.side)
+    }
+
+    pub fn opponents(&self) -> impl Iterator<Item = &PaddleState> {
+        let side = self.side;
+        self.paddles.iter().filter(move |paddle| paddle.side != side)
+    }
+
+    // the ball that reaches the paddle's face first and how long it takes,
+    // None while every ball is waiting for a serve or heading away
+    pub fn incoming(&self) -> Option<(f32, &BallState)> {
+        let own = self.own()?;
+        let side = self.side;
+        let across = side.across();
+        let face = own.position[across] + side.facing() * own.thickness() * 0.5;
+
+        self.balls.iter()
+            .filter(|ball| ball.velocity[across] != 0.0)
+            .filter_map(|ball| {
+                let time_to_reach = (face - ball.position[across]) / ball.velocity[across];
+                if time_to_reach >= 0.0 {
+                    Some((time_to_reach, ball))
+                } else {
+                    None
+                }
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+
+    // axis value that moves the paddle toward `target` along its lane,
+    // easing off when it's closer than a step's travel
+    pub fn toward(&self, target : f32, max_speed : f32) -> f32 {
+        let own = match self.own() {
+            Some(own) => own,
+            None => return 0.0
+        };
+        let reach = self.config.paddles.speed * self.dt.max(1.0 / self.config.tick_rate);
+        ((target - own.position[self.side.along()]) / reach)
+            .min(max_speed)
+            .max(-max_speed)
+    }
+}
+
+// the controller for a side of a match about to start; `replays` is where
+// Replay files are looked for
+pub fn create(kind : &ControllerKind, side : Side, config : &PongConfig, seed : u64, replays : &Path) -> Result<Box<dyn PaddleController>, String> {
+    let controller : Box<dyn PaddleController> = match kind {
+        ControllerKind::Keyboard => Box::new(Keyboard { axis : keyboard_axis(side).to_string() }),
+        ControllerKind::Gamepad(id) => Box::new(Gamepad { axis : format!("gamepad_{}", id), dead_zone : GAMEPAD_DEAD_ZONE }),
+        ControllerKind::Ai(difficulty) => Box::new(Ai::new(difficulty.settings(), config, side, seed)),
+        ControllerKind::Replay(file) => {
+            let replay = Replay::load(replays.join(file))
+                .map_err(|e| format!("{}: {}", file, e))?;
+            Box::new(Playback::new(&replay, side))
+        },
+        ControllerKind::Bot(name) => bot(name).ok_or_else(|| format!("no bot called {:?}", name))?
+    };
+    Ok(controller)
+}
+
+// bots by the name ControllerKind::Bot gives, add yours here
+pub fn bot(name : &str) -> Option<Box<dyn PaddleController>> {
+    match name {
+        "follower" => Some(Box::new(Follower)),
+        _ => None
+    }
+}
+
+pub fn keyboard_axis(side : Side) -> &'static str {
+    match side {
+        Side::Left => "left_paddle",
+        Side::Right => "right_paddle",
+        Side::Top => "top_paddle",
+        Side::Bottom => "bottom_paddle"
+    }
+}
+
+pub struct Keyboard {
+    pub axis : String
+}
+
+impl PaddleController for Keyboard {
+    fn movement(&mut self, observation : &Observation<'_>) -> f32 {
+        observation.input.axis_value(&self.axis).unwrap_or(0.0)
+    }
+}
+
+const GAMEPAD_DEAD_ZONE : f32 = 0.15;
+
+// a stick never quite rests at the center, the dead zone around it reads as
+// still and the rest of its travel is stretched to the full range
+pub struct Gamepad {
+    pub axis : String,
+    pub dead_zone : f32
+}
+
+impl PaddleController for Gamepad {
+    fn movement(&mut self, observation : &Observation<'_>) -> f32 {
+        let value = observation.input.axis_value(&self.axis).unwrap_or(0.0);
+        if value.abs() <= self.dead_zone {
+            0.0
+        } else {
+            value.signum() * (value.abs() - self.dead_zone) / (1.0 - self.dead_zone)
+        }
+    }
+}
+
+// the computer: looks at the ball every reaction_delay, aims for where it
+// will cross the paddle's lane, off by up to `error`, and moves there
+pub struct Ai {
+    pub settings : AiSettings,
+    pub target : f32, // along the paddle's lane
+    reaction_timer : f32,
+    rng : SimRng      // seeded, so a tournament match plays the same every time
+}
+
+impl Ai {
+    pub fn new(settings : AiSettings, config : &PongConfig, side : Side, seed : u64) -> Ai {
+        let extent = [config.arena.width, config.arena.height];
+        Ai {
+            settings,
+            target : extent[side.along()] * 0.5,
+            reaction_timer : 0.0,
+            rng : SimRng::new(seed)
+        }
+    }
+}
+
+impl PaddleController for Ai {
+    fn movement(&mut self, observation : &Observation<'_>) -> f32 {
+        let along = observation.side.along();
+        let config = observation.config;
+        let extent = [config.arena.width, config.arena.height];
+
+        self.reaction_timer -= observation.dt;
+        if self.reaction_timer <= 0.0 {
+            self.reaction_timer = self.settings.reaction_delay;
+
+            // only the ball that reaches this paddle first is worth tracking
+            self.target = match observation.incoming() {
+                Some((time_to_reach, ball)) => {
+                    if self.settings.predict_bounces {
+                        predict(ball.position[along] + ball.velocity[along] * time_to_reach, ball.radius, extent[along])
+                    } else {
+                        ball.position[along]
+                    }
+                },
+                None => extent[along] * 0.5
+            };
+
+            if self.settings.error > 0.0 {
+                self.target += self.rng.range(-self.settings.error, self.settings.error);
+            }
+        }
+
+        observation.toward(self.target, self.settings.max_speed)
+    }
+}
+
+// folds an unbounded position along a paddle's lane back into the arena,
+// mirroring it off the walls at either end
+fn predict(position : f32, radius : f32, extent : f32) -> f32 {
+    let span = extent - 2.0 * radius;
+    let folded = (position - radius).rem_euclid(2.0 * span);
+
+    if folded > span {
+        2.0 * span - folded + radius
+    } else {
+        folded + radius
+    }
+}
+
+// plays back one side of a recorded match, step by step, then stands still
+pub struct Playback {
+    movements : Vec<f32>
+}
+
+impl Playback {
+    pub fn new(replay : &Replay, side : Side) -> Playback {
+        let movements = replay.inputs.iter()
+            .flat_map(|run| std::iter::repeat_n(run.inputs.get(side), run.steps as usize))
+            .collect();
+        Playback { movements }
+    }
+}
+
+impl PaddleController for Playback {
+    fn movement(&mut self, observation : &Observation<'_>) -> f32 {
+        self.movements.get(observation.step as usize).cloned().unwrap_or(0.0)
+    }
+}
+
+// the simplest bot worth the name, and a start for writing one: keeps the
+// paddle level with the nearest incoming ball
+pub struct Follower;
+
+impl PaddleController for Follower {
+    fn movement(&mut self, observation : &Observation<'_>) -> f32 {
+        let along = observation.side.along();
+        match observation.incoming() {
+            Some((_, ball)) => observation.toward(ball.position[along], 1.0),
+            None => {
+                let extent = [observation.config.arena.width, observation.config.arena.height];
+                observation.toward(extent[along] * 0.5, 1.0)
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode},
+    prelude::*,
+    ui::{Anchor, UiText, UiTransform}
+};
+
+use crate::level::LevelList;
+use crate::menu::load_font;
+use crate::net::NetSession;
+use crate::play::{record_match, restart_match, save_recording, save_stats, set_score_text, GameMode};
+use crate::sim::Side;
+use crate::summary::MatchSummary;
+
+// pushed on top of Pong once the sim has a winner, the frozen arena stays visible underneath
+pub struct GameOver {
+    pub winner : Side,
+    next_level : Option<Entity>
+}
+
+impl GameOver {
+    pub fn new(winner : Side) -> GameOver {
+        GameOver { winner, next_level : None }
+    }
+}
+
+impl SimpleState for GameOver {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        let winner = self.winner;
+        set_score_text(world, |side| (if side == winner { "WIN" } else { "LOSE" }).to_string());
+        save_recording(world);
+        record_match(world);
+        save_stats(world);
+
+        // names the level N moves on to, when there are any to move between
+        let mode = *world.read_resource::<GameMode>();
+        let next_level = world.try_fetch::<LevelList>()
+            .filter(|levels| !levels.files.is_empty() && mode != GameMode::Online && mode != GameMode::Replay)
+            .map(|levels| format!("N: next level, {}", levels.next_label()));
+        if let Some(text) = next_level {
+            let font = load_font(world);
+            let transform = UiTransform::new(
+                "next_level".to_string(), Anchor::BottomMiddle, Anchor::Middle,
+                0.0, 60.0, 2.0, 480.0, 32.0
+            );
+            self.next_level = Some(world
+                .create_entity()
+                .with(transform)
+                .with(UiText::new(font, text, [1.0, 1.0, 1.0, 1.0], 20.0))
+                .build());
+        }
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        if let Some(next_level) = self.next_level.take() {
+            data.world.delete_entity(next_level).ok();
+        }
+    }
+
+    // keeps answering the other peer, which may still need our last inputs to see the end
+    fn update(&mut self, data : &mut StateData<'_, GameData<'_, '_ >>) -> SimpleTrans {
+        if let Some(mut net) = data.world.try_fetch_mut::<NetSession>() {
+            net.receive();
+            net.send();
+        }
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) => {
+                Trans::Quit
+            },
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "stats" => {
+                Trans::Push(Box::new(MatchSummary::default()))
+            },
+            // an online rematch would need both peers to agree, start a new session instead
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "rematch"
+                && *data.world.read_resource::<GameMode>() != GameMode::Online => {
+                restart_match(data.world);
+                Trans::Pop
+            },
+            // a replay keeps the level it was recorded on
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "next_level"
+                && *data.world.read_resource::<GameMode>() != GameMode::Online
+                && *data.world.read_resource::<GameMode>() != GameMode::Replay => {
+                if let Some(mut levels) = data.world.try_fetch_mut::<LevelList>() {
+                    levels.select_next();
+                }
+                restart_match(data.world);
+                Trans::Pop
+            },
+            _ => Trans::None
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,241 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use ron::de::from_str;
+use ron::ser::to_string;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Side;
+
+// bumped when a change to the records can't be read with serde defaults alone;
+// version 1 had room for two players only, it's upgraded as it loads, and so
+// is a file with no version at all, which can only be that old
+pub const HISTORY_VERSION : u32 = 2;
+
+// every finished match, oldest first
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MatchHistory {
+    pub version : u32,
+    pub matches : Vec<MatchRecord>
+}
+
+// fields added later get their defaults when an older file is read
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MatchRecord {
+    pub timestamp : u64,       // unix seconds at the end of the match
+    pub mode : String,
+    pub sides : Vec<Side>,     // that played, the lists below follow their order
+    pub players : Vec<String>, // the computer shows as "AI (difficulty)"
+    pub scores : Vec<u32>,     // points in the last set
+    pub sets : Vec<u32>,
+    pub winner : Option<Side>,
+    pub longest_rally : u32,
+    pub duration : f32         // seconds of play
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Version {
+    version : u32
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct MatchHistoryV1 {
+    matches : Vec<MatchRecordV1>
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct MatchRecordV1 {
+    timestamp : u64,
+    mode : String,
+    players : [String; 2],
+    scores : [u32; 2],
+    sets : [u32; 2],
+    winner : Option<Side>,
+    longest_rally : u32,
+    duration : f32
+}
+
+impl From<MatchRecordV1> for MatchRecord {
+    fn from(record : MatchRecordV1) -> Self {
+        MatchRecord {
+            timestamp : record.timestamp,
+            mode : record.mode,
+            sides : vec![Side::Left, Side::Right],
+            players : record.players.to_vec(),
+            scores : record.scores.to_vec(),
+            sets : record.sets.to_vec(),
+            winner : record.winner,
+            longest_rally : record.longest_rally,
+            duration : record.duration
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name : String,
+    pub wins : u32,
+    pub losses : u32
+}
+
+impl MatchHistory {
+    pub fn load<P : AsRef<Path>>(path : P) -> Result<MatchHistory, HistoryError> {
+        MatchHistory::from_text(&fs::read_to_string(path)?)
+    }
+
+    fn from_text(text : &str) -> Result<MatchHistory, HistoryError> {
+        match parse::<Version>(text)?.version {
+            0 | 1 => {
+                let old : MatchHistoryV1 = parse(text)?;
+                Ok(MatchHistory {
+                    version : HISTORY_VERSION,
+                    matches : old.matches.into_iter().map(MatchRecord::from).collect()
+                })
+            },
+            HISTORY_VERSION => parse(text),
+            version => Err(HistoryError::Version(version)) // only a newer build writes these
+        }
+    }
+
+    // losing the history is better than not starting: a missing file is an
+    // empty history, and one that can't be read is moved aside, not overwritten
+    pub fn load_or_recover<P : AsRef<Path>>(path : P) -> MatchHistory {
+        let path = path.as_ref();
+        if !path.exists() {
+            return MatchHistory::default();
+        }
+
+        match MatchHistory::load(path) {
+            Ok(history) => history,
+            Err(e) => {
+                let backup = path.with_extension("ron.unreadable");
+                eprintln!("{}, moving it to {} and starting a new one", e, backup.display());
+                fs::rename(path, &backup).ok();
+                MatchHistory::default()
+            }
+        }
+    }
+
+    // written next to the real file first, so a crash mid-write can't leave it half done
+    pub fn save<P : AsRef<Path>>(&self, path : P) -> Result<(), HistoryError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let history = MatchHistory { version : HISTORY_VERSION, matches : self.matches.clone() };
+        let text = to_string(&history)
+            .map_err(|e| HistoryError::Parse(e.to_string()))?;
+        let temporary = path.with_extension("ron.tmp");
+        fs::write(&temporary, text)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    // win and loss records per name, best first
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings : Vec<Standing> = Vec::new();
+
+        for record in self.matches.iter() {
+            let winner = match record.winner {
+                Some(winner) => winner,
+                None => continue
+            };
+            for (side, name) in record.sides.iter().zip(record.players.iter()) {
+                let index = match standings.iter().position(|standing| standing.name == *name) {
+                    Some(index) => index,
+                    None => {
+                        standings.push(Standing { name : name.clone(), wins : 0, losses : 0 });
+                        standings.len() - 1
+                    }
+                };
+                if *side == winner {
+                    standings[index].wins += 1;
+                } else {
+                    standings[index].losses += 1;
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| b.wins.cmp(&a.wins)
+            .then(a.losses.cmp(&b.losses))
+            .then(a.name.cmp(&b.name)));
+        standings
+    }
+}
+
+fn parse<T : DeserializeOwned>(text : &str) -> Result<T, HistoryError> {
+    from_str(text).map_err(|e| HistoryError::Parse(e.to_string()))
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Io(std::io::Error),
+    Parse(String),
+    Version(u32)
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Io(e) => write!(f, "could not read or write match history: {}", e),
+            HistoryError::Parse(e) => write!(f, "could not parse match history: {}", e),
+            HistoryError::Version(version) => write!(f, "match history is version {}, newer than this build reads ({})", version, HISTORY_VERSION)
+        }
+    }
+}
+
+impl Error for HistoryError {}
+
+impl From<std::io::Error> for HistoryError {
+    fn from(e : std::io::Error) -> Self {
+        HistoryError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_MATCH : &str = "(timestamp : 10, mode : \"VsAi\", players : (\"Ann\", \"AI\"), scores : (11, 7), sets : (1, 0), winner : Some(Left), longest_rally : 12, duration : 90.0)";
+
+    #[test]
+    fn file_without_a_version_is_read_as_version_1() {
+        let history = MatchHistory::from_text(&format!("(matches : [{}])", V1_MATCH)).unwrap();
+
+        assert_eq!(history.version, HISTORY_VERSION);
+        assert_eq!(history.matches.len(), 1);
+        assert_eq!(history.matches[0].sides, vec![Side::Left, Side::Right]);
+        assert_eq!(history.matches[0].players, vec!["Ann".to_string(), "AI".to_string()]);
+        assert_eq!(history.matches[0].scores, vec![11, 7]);
+    }
+
+    #[test]
+    fn version_1_is_upgraded() {
+        let history = MatchHistory::from_text(&format!("(version : 1, matches : [{}])", V1_MATCH)).unwrap();
+        assert_eq!(history.standings()[0], Standing { name : "Ann".to_string(), wins : 1, losses : 0 });
+    }
+
+    #[test]
+    fn current_version_round_trips() {
+        let history = MatchHistory::from_text(&format!("(matches : [{}])", V1_MATCH)).unwrap();
+        let text = to_string(&history).unwrap();
+        assert_eq!(MatchHistory::from_text(&text).unwrap(), history);
+    }
+
+    #[test]
+    fn only_a_newer_version_is_refused() {
+        match MatchHistory::from_text(&format!("(version : {}, matches : [])", HISTORY_VERSION + 1)) {
+            Err(HistoryError::Version(version)) => assert_eq!(version, HISTORY_VERSION + 1),
+            other => panic!("expected a version error, got {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{Anchor, FontHandle, UiButton, UiEvent, UiEventType, UiText, UiTransform}
+};
+
+use crate::history::MatchHistory;
+use crate::menu::{create_button, delete_button, is_clicked, load_font};
+use crate::play::history_path;
+
+const MAX_ROWS : usize = 8;
+const ROW_HEIGHT : f32 = 30.0;
+
+// win/loss records of everyone in the match history, pushed over the main menu
+#[derive(Default)]
+pub struct Leaderboard {
+    texts : Vec<Entity>,
+    back : Option<UiButton>
+}
+
+impl Leaderboard {
+    fn create_text(&mut self, world : &mut World, font : FontHandle, text : String, y : f32, size : f32) {
+        let transform = UiTransform::new(
+            "leaderboard".to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
+            0.0, y, 1.0, 500.0, size + 10.0
+        );
+        self.texts.push(world
+            .create_entity()
+            .with(transform)
+            .with(UiText::new(font, text, [1.0, 1.0, 1.0, 1.0], size))
+            .build());
+    }
+}
+
+impl SimpleState for Leaderboard {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        let font = load_font(world);
+
+        let standings = history_path()
+            .map(|path| MatchHistory::load_or_recover(&path).standings())
+            .unwrap_or_default();
+
+        self.create_text(world, font.clone(), "LEADERBOARD".to_string(), -40.0, 40.0);
+        if standings.is_empty() {
+            self.create_text(world, font.clone(), "No finished matches yet".to_string(), -120.0, 20.0);
+        }
+        for (rank, standing) in standings.iter().take(MAX_ROWS).enumerate() {
+            let row = format!("{}. {}  {}-{}", rank + 1, standing.name, standing.wins, standing.losses);
+            self.create_text(world, font.clone(), row, -110.0 - rank as f32 * ROW_HEIGHT, 20.0);
+        }
+
+        self.back = Some(create_button(world, font, "Back", -200.0));
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        for text in self.texts.drain(..) {
+            data.world.delete_entity(text).ok();
+        }
+        if let Some(back) = self.back.take() {
+            delete_button(data.world, &back);
+        }
+    }
+
+    fn handle_event(&mut self, _data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Window(event) if is_key_down(event, VirtualKeyCode::Escape) => Trans::Pop,
+            StateEvent::Ui(UiEvent { event_type : UiEventType::Click, target }) if is_clicked(&self.back, *target) => {
+                Trans::Pop
+            },
+            _ => Trans::None
+        }
+    }
+}
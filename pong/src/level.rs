@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use ron::de::from_reader;
+use serde::{Deserialize, Serialize};
+
+use crate::config::PongConfig;
+
+// Obstacles placed in the arena. Levels are RON files in config/levels/; the
+// one picked goes into the match's PongConfig, so replays and the other peer
+// of an online match play the same arena.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Level {
+    pub name : String,
+    pub obstacles : Vec<ObstacleConfig>
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ObstacleConfig {
+    pub shape : Shape,
+    pub position : [f32; 2], // center, in arena units
+    #[serde(default)]
+    pub path : Option<PathConfig>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Shape {
+    Block { width : f32, height : f32 }, // the ball bounces off it like off a wall
+    Bumper { radius : f32, boost : f32 } // round, the ball leaves it `boost` times faster
+}
+
+impl Shape {
+    // half the size of the box around the shape
+    pub fn half_extent(&self) -> [f32; 2] {
+        match *self {
+            Shape::Block { width, height } => [width * 0.5, height * 0.5],
+            Shape::Bumper { radius, .. } => [radius, radius]
+        }
+    }
+}
+
+// a moving obstacle swings back and forth between its position and position + offset
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct PathConfig {
+    pub offset : [f32; 2],
+    pub period : f32, // seconds for the way there and back
+    #[serde(default)]
+    pub phase : f32   // 0.0 - 1.0, how far through the swing it starts
+}
+
+impl Level {
+    // parses the file and checks the level fits the config's arena
+    pub fn load<P : AsRef<Path>>(path : P, config : &PongConfig) -> Result<Level, LevelError> {
+        let file = File::open(path)?;
+        let level : Level = from_reader(file)
+            .map_err(|e| LevelError::Parse(e.to_string()))?;
+
+        level.validate(config).map_err(LevelError::Invalid)?;
+        Ok(level)
+    }
+
+    // every obstacle stays inside the arena and out of the paddles' lanes
+    // along its whole path, and never covers the center a serve starts from
+    pub fn validate(&self, config : &PongConfig) -> Result<(), String> {
+        let arena = &config.arena;
+        let lane = config.paddles.width;
+        let top_lane = if config.four_player.enabled { lane } else { 0.0 };
+        let ball_radius = config.ball.radius;
+
+        for (index, obstacle) in self.obstacles.iter().enumerate() {
+            let name = format!("obstacle {} of level {:?}", index + 1, self.name);
+            match obstacle.shape {
+                Shape::Block { width, height } if !(width > 0.0 && height > 0.0) => {
+                    return Err(format!("{} needs a positive size, got {} x {}", name, width, height));
+                },
+                Shape::Bumper { radius, boost } if !(radius > 0.0 && boost >= 1.0) => {
+                    return Err(format!("{} needs a positive radius and a boost of at least 1.0, got {} and {}", name, radius, boost));
+                },
+                _ => {}
+            }
+
+            let offset = match obstacle.path {
+                Some(path) if path.period.is_nan() || path.period <= 0.0 => {
+                    return Err(format!("{} needs a positive path period, got {}", name, path.period));
+                },
+                Some(path) => path.offset,
+                None => [0.0, 0.0]
+            };
+
+            // the box the obstacle sweeps through on its way
+            let half = obstacle.shape.half_extent();
+            let min = [
+                obstacle.position[0].min(obstacle.position[0] + offset[0]) - half[0],
+                obstacle.position[1].min(obstacle.position[1] + offset[1]) - half[1]
+            ];
+            let max = [
+                obstacle.position[0].max(obstacle.position[0] + offset[0]) + half[0],
+                obstacle.position[1].max(obstacle.position[1] + offset[1]) + half[1]
+            ];
+
+            if !(min[0] >= lane && max[0] <= arena.width - lane && min[1] >= top_lane && max[1] <= arena.height - top_lane) {
+                return Err(format!("{} leaves the arena or crosses a paddle's lane", name));
+            }
+
+            let center = [arena.width * 0.5, arena.height * 0.5];
+            if center[0] > min[0] - ball_radius && center[0] < max[0] + ball_radius
+                && center[1] > min[1] - ball_radius && center[1] < max[1] + ball_radius {
+                return Err(format!("{} covers the center, where every serve starts", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// the level files to pick from and the one picked, None for the empty arena
+#[derive(Debug, Clone, Default)]
+pub struct LevelList {
+    pub files : Vec<PathBuf>,
+    pub selected : Option<usize>
+}
+
+impl LevelList {
+    // every .ron file in the directory, by name; a missing directory means no levels
+    pub fn scan<P : AsRef<Path>>(dir : P) -> LevelList {
+        let mut files : Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+                .collect())
+            .unwrap_or_default();
+        files.sort();
+
+        LevelList { files, selected : None }
+    }
+
+    // steps through the files, then back to the empty arena
+    pub fn select_next(&mut self) {
+        self.selected = self.next();
+    }
+
+    fn next(&self) -> Option<usize> {
+        match self.selected {
+            None if !self.files.is_empty() => Some(0),
+            Some(index) if index + 1 < self.files.len() => Some(index + 1),
+            _ => None
+        }
+    }
+
+    pub fn label(&self) -> String {
+        self.label_of(self.selected)
+    }
+
+    // what select_next would pick
+    pub fn next_label(&self) -> String {
+        self.label_of(self.next())
+    }
+
+    fn label_of(&self, index : Option<usize>) -> String {
+        index
+            .and_then(|index| self.files[index].file_stem())
+            .map_or("none".to_string(), |stem| stem.to_string_lossy().into_owned())
+    }
+
+    // reads the picked level from disk again, so edits made between matches show up
+    pub fn load(&self, config : &PongConfig) -> Result<Level, LevelError> {
+        match self.selected {
+            Some(index) => Level::load(&self.files[index], config),
+            None => Ok(Level::default())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String)
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(e) => write!(f, "could not read level: {}", e),
+            LevelError::Parse(e) => write!(f, "could not parse level: {}", e),
+            LevelError::Invalid(e) => write!(f, "invalid level: {}", e)
+        }
+    }
+}
+
+impl Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(e : std::io::Error) -> Self {
+        LevelError::Io(e)
+    }
+}
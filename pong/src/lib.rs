@@ -0,0 +1,11 @@
+// The engine independent half of the game: the rules, their config, levels,
+// replays, paddle controllers and match stats. The game in main.rs draws and plays them
+// with amethyst, the pong-tournament binary steps them without a window.
+
+pub mod args;
+pub mod config;
+pub mod controller;
+pub mod level;
+pub mod replay;
+pub mod sim;
+pub mod stats;
@@ -0,0 +1,83 @@
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{Anchor, UiText, UiTransform}
+};
+
+use crate::audio::initialize_audio;
+use crate::menu::load_font;
+use crate::net::{NetRole, NetSession, NetStatus};
+use crate::play::{GameMode, Pong};
+
+// waits for the other peer of an online match, then starts it
+pub struct Lobby {
+    pub role : NetRole,
+    message : Option<Entity>
+}
+
+impl Lobby {
+    pub fn new(role : NetRole) -> Lobby {
+        Lobby { role, message : None }
+    }
+}
+
+impl SimpleState for Lobby {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        initialize_audio(world);
+        let font = load_font(world);
+
+        // the port asked for may be 0, the OS picks one then
+        let bound = world.read_resource::<NetSession>().local_address().ok();
+        let text = match &self.role {
+            NetRole::Host { port } => format!("Waiting for a player on port {}", bound.map_or(*port, |address| address.port())),
+            NetRole::Join { address } => format!("Joining {}", address)
+        };
+        let transform = UiTransform::new(
+            "lobby".to_string(), Anchor::Middle, Anchor::Middle,
+            0.0, 0.0, 1.0, 600.0, 40.0
+        );
+        self.message = Some(world
+            .create_entity()
+            .with(transform)
+            .with(UiText::new(font, text, [1.0, 1.0, 1.0, 1.0], 25.0))
+            .build());
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        if let Some(message) = self.message.take() {
+            data.world.delete_entity(message).ok();
+        }
+    }
+
+    fn handle_event(&mut self, _data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) => {
+                Trans::Quit
+            },
+            _ => Trans::None
+        }
+    }
+
+    fn update(&mut self, data : &mut StateData<'_, GameData<'_, '_ >>) -> SimpleTrans {
+        let status = {
+            let mut net = data.world.write_resource::<NetSession>();
+            net.receive();
+            net.send();
+            net.status().clone()
+        };
+
+        match status {
+            NetStatus::Running => {
+                data.world.insert(GameMode::Online);
+                Trans::Switch(Box::new(Pong::default()))
+            },
+            NetStatus::Failed(e) => {
+                eprintln!("{}", e);
+                Trans::Quit
+            },
+            _ => Trans::None
+        }
+    }
+}
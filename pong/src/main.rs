@@ -1,4 +1,5 @@
 use amethyst::{
+    audio::{AudioBundle, DjSystemDesc},
     prelude::*, // containing ? 
     renderer::{
         plugins::{RenderFlat2D, RenderToWindow}, // plugins to render
@@ -10,9 +11,32 @@ use amethyst::{
     input::{InputBundle, StringBindings},
     ui::{UiBundle, RenderUi}
 };
-use crate::pong::Pong;
+use crate::audio::Music;
+use crate::config::{AudioConfig, PongConfig};
+use crate::level::LevelList;
+use crate::lobby::Lobby;
+use crate::main_menu::MainMenu;
+use crate::net::{NetRole, NetSession};
+use crate::play::{GameMode, Pong};
+use crate::replay::{dashed, Replay, ReplayPlayer};
 
-mod pong;
+// the rules and the rest that doesn't need amethyst are in the library, lib.rs
+use pong::args::flag_value;
+use pong::{config, controller, level, replay, sim, stats};
+
+mod audio;
+mod audio_settings;
+mod game_over;
+mod history;
+mod leaderboard;
+mod lobby;
+mod main_menu;
+mod menu;
+mod net;
+mod paused;
+mod play;
+mod settings;
+mod summary;
 mod systems;
 
 fn main() -> amethyst::Result<()> {
@@ -20,6 +44,26 @@ fn main() -> amethyst::Result<()> {
 
     let app_root = application_root_dir()?; // project root
     let display_config_path = app_root.join("config").join("display.ron"); // display config
+    let mut pong_config = PongConfig::load(app_root.join("config").join("pong.ron"))?; // match rules, validated on load
+
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    let role = NetRole::from_args(&args, &mut pong_config.net) // --host [port] or --join <address> for online play
+        .map_err(amethyst::Error::from_string)?;
+
+    // --verify <replay> plays a replay without a window and checks it ends on its recorded score
+    if let Some(path) = flag_value(&args, "--verify") {
+        let replay = Replay::load(path)?;
+        replay.verify()?;
+        println!("{} reproduces its match, {} in sets {}", path, dashed(&replay.scores), dashed(&replay.sets));
+        return Ok(());
+    }
+    let replay = match flag_value(&args, "--replay") {
+        Some(path) => Some(Replay::load(path)?),
+        None => None
+    };
+
+    let audio_config = AudioConfig::load_with_changes(app_root.join("config").join("audio.ron"), app_root.join("data").join("audio.ron"));
+    let levels = LevelList::scan(app_root.join("config").join("levels")); // picked in the settings
 
     let bindings_path = app_root.join("config").join("bindings.ron");
     let input_bundle = InputBundle::<StringBindings>::new()
@@ -42,15 +86,44 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(TransformBundle::new())?
         .with_bundle(input_bundle)?
         .with_bundle(UiBundle::<StringBindings>::new())?
-        .with(systems::PaddleSystem, "paddle_system", &["input_system"])
-        .with(systems::MoveBallSystem, "ball_system", &[])
-        .with(systems::BounceSystem, "bounce_system", &["paddle_system", "ball_system"])
-        .with(systems::WinnerSystem, "winner_system", &["ball_system"]);
+        .with_bundle(AudioBundle::default())? // plays nothing without an output device
+        .with_system_desc(DjSystemDesc::new(|music : &mut Music| music.music.next()), "dj_system", &[])
+        .with(systems::ViewportSystem::default(), "viewport_system", &[]); // gameplay systems are dispatched by the Pong state
 
     let assets_dir = app_root.join("assets"); // asset folder directory
-    let mut game = Application::new(assets_dir, Pong::default(), game_data)?; // create new application, with game data
+    let mut game = match (role, replay) {
+        (_, Some(replay)) => {
+            println!("Watching replay: P pauses, Left and Right change the speed, Enter starts over");
+            Application::build(assets_dir, Pong::default())?
+                .with_resource(pong_config)
+                .with_resource(audio_config)
+                .with_resource(levels)
+                .with_resource(GameMode::Replay)
+                .with_resource(ReplayPlayer::new(replay))
+                .build(game_data)?
+        },
+        (Some(role), None) => {
+            let net = match &role {
+                NetRole::Host { port } => NetSession::host(pong_config.clone(), *port, rand::random())?,
+                NetRole::Join { address } => NetSession::join(pong_config.clone(), address)?
+            };
+            Application::build(assets_dir, Lobby::new(role))?
+                .with_resource(pong_config)
+                .with_resource(audio_config)
+                .with_resource(levels)
+                .with_resource(net)
+                .build(game_data)?
+        },
+        (None, None) => {
+            Application::build(assets_dir, MainMenu::default())? // create new application, with game data
+                .with_resource(pong_config)
+                .with_resource(audio_config)
+                .with_resource(levels)
+                .build(game_data)?
+        }
+    };
 
     game.run(); // start game loop
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,104 @@
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{Anchor, UiButton, UiEvent, UiEventType, UiText, UiTransform}
+};
+
+use crate::audio::initialize_audio;
+use crate::leaderboard::Leaderboard;
+use crate::menu::{create_button, delete_button, is_clicked, load_font};
+use crate::play::{GameMode, Pong};
+use crate::settings::Settings;
+
+#[derive(Default)]
+pub struct MainMenu {
+    title : Option<Entity>,
+    vs_ai : Option<UiButton>,
+    two_player : Option<UiButton>,
+    four_player : Option<UiButton>,
+    practice : Option<UiButton>,
+    leaderboard : Option<UiButton>,
+    settings : Option<UiButton>,
+    quit : Option<UiButton>
+}
+
+impl MainMenu {
+    fn start(&self, world : &mut World, mode : GameMode) -> SimpleTrans {
+        world.insert(mode);
+        Trans::Switch(Box::new(Pong::default()))
+    }
+}
+
+impl SimpleState for MainMenu {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        initialize_audio(world);
+        let font = load_font(world);
+
+        let title_transform = UiTransform::new(
+            "title".to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
+            0.0, -40.0, 1.0, 300.0, 60.0
+        );
+        self.title = Some(world
+            .create_entity()
+            .with(title_transform)
+            .with(UiText::new(font.clone(), "PONG".to_string(), [1.0, 1.0, 1.0, 1.0], 60.0))
+            .build());
+
+        self.vs_ai = Some(create_button(world, font.clone(), "1P vs AI", 130.0));
+        self.two_player = Some(create_button(world, font.clone(), "2P local", 84.0));
+        self.four_player = Some(create_button(world, font.clone(), "4 players", 38.0));
+        self.practice = Some(create_button(world, font.clone(), "Practice", -8.0));
+        self.leaderboard = Some(create_button(world, font.clone(), "Leaderboard", -54.0));
+        self.settings = Some(create_button(world, font.clone(), "Settings", -100.0));
+        self.quit = Some(create_button(world, font, "Quit", -146.0));
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        if let Some(title) = self.title.take() {
+            data.world.delete_entity(title).ok();
+        }
+        let buttons = [self.vs_ai.take(), self.two_player.take(), self.four_player.take(), self.practice.take(), self.leaderboard.take(), self.settings.take(), self.quit.take()];
+        for button in buttons.iter().flatten() {
+            delete_button(data.world, button);
+        }
+    }
+
+    // the settings and leaderboard screens draw over the menu, hide ours meanwhile
+    fn on_pause(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        self.on_stop(data);
+    }
+
+    fn on_resume(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        self.on_start(data);
+    }
+
+    fn handle_event(&mut self, data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) => {
+                Trans::Quit
+            },
+            StateEvent::Ui(UiEvent { event_type : UiEventType::Click, target }) => {
+                if is_clicked(&self.vs_ai, *target) {
+                    self.start(data.world, GameMode::VsAi)
+                } else if is_clicked(&self.two_player, *target) {
+                    self.start(data.world, GameMode::TwoPlayer)
+                } else if is_clicked(&self.four_player, *target) {
+                    self.start(data.world, GameMode::FourPlayer)
+                } else if is_clicked(&self.practice, *target) {
+                    self.start(data.world, GameMode::Practice)
+                } else if is_clicked(&self.leaderboard, *target) {
+                    Trans::Push(Box::new(Leaderboard::default()))
+                } else if is_clicked(&self.settings, *target) {
+                    Trans::Push(Box::new(Settings::default()))
+                } else if is_clicked(&self.quit, *target) {
+                    Trans::Quit
+                } else {
+                    Trans::None
+                }
+            },
+            _ => Trans::None
+        }
+    }
+}
\ No newline at end of file
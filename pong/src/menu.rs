@@ -0,0 +1,56 @@
+use amethyst::{
+    assets::Loader,
+    ecs::prelude::Entity,
+    prelude::*,
+    ui::{Anchor, FontHandle, Stretch, TtfFormat, UiButton, UiButtonBuilder, UiImage, UiTransform}
+};
+
+// shared pieces for the menu and overlay states
+
+pub fn load_font(world : &mut World) -> FontHandle {
+    world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource()
+    )
+}
+
+// dims the whole window, buttons go on top of it
+pub fn create_backdrop(world : &mut World) -> Entity {
+    let transform = UiTransform::new(
+        "backdrop".to_string(), Anchor::Middle, Anchor::Middle,
+        0.0, 0.0, 10.0, 0.0, 0.0
+    ).with_stretch(Stretch::XY { x_margin : 0.0, y_margin : 0.0, keep_aspect_ratio : false });
+
+    world
+        .create_entity()
+        .with(transform)
+        .with(UiImage::SolidColor([0.0, 0.0, 0.0, 0.7]))
+        .build()
+}
+
+pub fn create_button(world : &mut World, font : FontHandle, label : &str, y : f32) -> UiButton {
+    let (_, button) = UiButtonBuilder::<(), u32>::new(label)
+        .with_font(font)
+        .with_font_size(24.0)
+        .with_anchor(Anchor::Middle)
+        .with_position(0.0, y)
+        .with_size(240.0, 44.0)
+        .with_layer(11.0)
+        .with_text_color([1.0, 1.0, 1.0, 1.0])
+        .with_hover_text_color([1.0, 0.8, 0.2, 1.0])
+        .with_image(UiImage::SolidColor([0.15, 0.15, 0.15, 1.0]))
+        .build_from_world(world);
+
+    button
+}
+
+pub fn delete_button(world : &mut World, button : &UiButton) {
+    world.delete_entity(button.text_entity).ok();
+    world.delete_entity(button.image_entity).ok();
+}
+
+pub fn is_clicked(button : &Option<UiButton>, target : Entity) -> bool {
+    button.as_ref().is_some_and(|button| button.image_entity == target || button.text_entity == target)
+}
\ No newline at end of file
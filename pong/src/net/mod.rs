@@ -0,0 +1,466 @@
+// Online two-player over UDP. The host listens on a port, the joiner says
+// hello and gets back the seed and match config, and from then on both
+// peers run the same PongSim through Rollback and trade their paddle inputs
+// every frame. Nothing here touches amethyst, so two sessions can play each
+// other in one process over loopback.
+
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::config::{NetConfig, PongConfig};
+use crate::sim::{PongSim, Side};
+use self::protocol::{Message, MAX_INPUTS_PER_PACKET};
+use self::rollback::Rollback;
+
+mod protocol;
+mod rollback;
+
+const HELLO_INTERVAL : Duration = Duration::from_millis(250);
+const MAX_DATAGRAM : usize = 65_507;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetRole {
+    Host { port : u16 },
+    Join { address : String }
+}
+
+impl NetRole {
+    // --host [port], --join <address> and --input-delay <steps>; None without
+    // --host or --join, the game starts at the main menu then
+    pub fn from_args(args : &[String], net : &mut NetConfig) -> Result<Option<NetRole>, String> {
+        let mut role = None;
+        let mut args = args.iter().peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--host" => {
+                    if let Some(port) = args.peek().and_then(|port| port.parse().ok()) {
+                        net.port = port;
+                        args.next();
+                    }
+                    role = Some(NetRole::Host { port : net.port });
+                },
+                "--join" => {
+                    let address = args.next().ok_or("--join needs an address, like 127.0.0.1:7777")?;
+                    role = Some(NetRole::Join { address : address.clone() });
+                },
+                "--input-delay" => {
+                    net.input_delay = args.next()
+                        .and_then(|delay| delay.parse().ok())
+                        .ok_or("--input-delay needs a number of sim steps")?;
+                },
+                _ => {}
+            }
+        }
+        Ok(role)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetStatus {
+    Connecting,
+    Running,
+    Desynced { frame : u32 },
+    Disconnected,
+    Failed(String)
+}
+
+pub struct NetSession {
+    socket : UdpSocket,
+    net : NetConfig,
+    config : PongConfig, // the host's, the joiner takes it over on welcome
+    seed : u64,
+    host_address : Option<SocketAddr>, // where a joiner says hello
+    peer : Option<SocketAddr>,
+    status : NetStatus,
+    rollback : Option<Rollback>,
+    remote_ack : u32, // frames of our inputs the peer has
+    last_heard : Instant,
+    last_hello : Option<Instant>,
+    waiting_since : Option<Instant> // advance has been held back by the peer's inputs since
+}
+
+impl NetSession {
+    pub fn host(config : PongConfig, port : u16, seed : u64) -> io::Result<NetSession> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        NetSession::new(socket, config, seed, None)
+    }
+
+    pub fn join(config : PongConfig, address : &str) -> io::Result<NetSession> {
+        let host_address = address.to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no address found for {}", address)))?;
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        NetSession::new(socket, config, 0, Some(host_address))
+    }
+
+    fn new(socket : UdpSocket, config : PongConfig, seed : u64, host_address : Option<SocketAddr>) -> io::Result<NetSession> {
+        socket.set_nonblocking(true)?;
+
+        Ok(NetSession {
+            socket,
+            net : config.net.clone(),
+            config,
+            seed,
+            host_address,
+            peer : None,
+            status : NetStatus::Connecting,
+            rollback : None,
+            remote_ack : 0,
+            last_heard : Instant::now(),
+            last_hello : None,
+            waiting_since : None
+        })
+    }
+
+    pub fn local_address(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn status(&self) -> &NetStatus {
+        &self.status
+    }
+
+    // the match both peers play, only final once Running
+    pub fn config(&self) -> &PongConfig {
+        &self.config
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // the host plays left
+    pub fn local_side(&self) -> Side {
+        match self.host_address {
+            Some(_) => Side::Right,
+            None => Side::Left
+        }
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.rollback.as_ref().map_or(0, |rollback| rollback.frame())
+    }
+
+    // wrong predictions rewound so far
+    pub fn rewinds(&self) -> u32 {
+        self.rollback.as_ref().map_or(0, |rollback| rollback.rewinds())
+    }
+
+    // takes in everything that arrived since the last call
+    pub fn receive(&mut self) {
+        let mut buffer = vec![0; MAX_DATAGRAM];
+
+        loop {
+            let (length, from) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(_) => continue // e.g. the peer's port closed on windows, the timeout handles it
+            };
+            if let Some(message) = Message::decode(&buffer[..length]) {
+                self.handle(message, from);
+            }
+        }
+
+        if self.status == NetStatus::Running && self.last_heard.elapsed().as_secs_f32() > self.net.timeout {
+            self.status = NetStatus::Disconnected;
+        }
+    }
+
+    // sends our side of the conversation: hellos while joining, inputs and checksums once running
+    pub fn send(&mut self) {
+        match (&self.status, self.host_address) {
+            (NetStatus::Connecting, Some(host_address)) if self.last_hello.is_none_or(|last| last.elapsed() >= HELLO_INTERVAL) => {
+                self.send_to(&Message::Hello, host_address);
+                self.last_hello = Some(Instant::now());
+            },
+            // a desynced peer still sends, so the other side finds out too
+            (NetStatus::Running, _) | (NetStatus::Desynced { .. }, _) => {
+                let (peer, rollback) = match (self.peer, self.rollback.as_mut()) {
+                    (Some(peer), Some(rollback)) => (peer, rollback),
+                    _ => return
+                };
+
+                let start = self.remote_ack.min(rollback.local_inputs().len() as u32);
+                let inputs = rollback.local_inputs()[start as usize..].iter()
+                    .take(MAX_INPUTS_PER_PACKET)
+                    .cloned()
+                    .collect();
+                let mut messages = vec![Message::Input { ack : rollback.remote_frames(), start, inputs }];
+                for (frame, checksum) in rollback.take_checksums() {
+                    messages.push(Message::Checksum { frame, checksum });
+                }
+
+                for message in messages {
+                    self.send_to(&message, peer);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    // one sim step with our paddle's input, rewinding first if the peer's
+    // inputs showed a prediction was wrong; false while waiting on the peer.
+    // A peer that still answers but stops sending new inputs for longer than
+    // the timeout counts as gone as well
+    pub fn advance(&mut self, sim : &mut PongSim, local_input : f32) -> bool {
+        if self.status != NetStatus::Running {
+            return false;
+        }
+        let rollback = match self.rollback.as_mut() {
+            Some(rollback) => rollback,
+            None => return false
+        };
+
+        let advanced = rollback.advance(sim, local_input);
+        if let Some(frame) = rollback.desync() {
+            self.status = NetStatus::Desynced { frame };
+        }
+
+        if advanced {
+            self.waiting_since = None;
+        } else if self.waiting_since.get_or_insert_with(Instant::now).elapsed().as_secs_f32() > self.net.timeout {
+            self.status = NetStatus::Disconnected;
+        }
+        advanced
+    }
+
+    // the sim's winner once both peers' inputs confirm it, a winner on a
+    // predicted input only ends the match when the real one agrees; until
+    // then keep advancing, so the rollback can take it back
+    pub fn confirmed_winner(&self, sim : &PongSim) -> Option<Side> {
+        self.rollback.as_ref().and_then(|rollback| rollback.confirmed_winner(sim))
+    }
+
+    fn handle(&mut self, message : Message, from : SocketAddr) {
+        if self.peer.is_some_and(|peer| peer != from) {
+            return;
+        }
+
+        match message {
+            Message::Hello if self.host_address.is_none() => {
+                if self.peer.is_none() {
+                    self.peer = Some(from);
+                    self.start();
+                }
+                // answered every time, the joiner keeps asking until a welcome gets through
+                match ron::ser::to_string(&self.config) {
+                    Ok(config) => self.send_to(&Message::Welcome { seed : self.seed, config }, from),
+                    Err(e) => self.status = NetStatus::Failed(format!("could not send the match config: {}", e))
+                }
+            },
+            Message::Welcome { seed, config } if self.host_address == Some(from) && self.status == NetStatus::Connecting => {
+                let parsed = ron::de::from_str::<PongConfig>(&config)
+                    .map_err(|e| e.to_string())
+                    .and_then(|config| config.validate().map(|_| config).map_err(|e| e.to_string()));
+
+                match parsed {
+                    Ok(mut config) => {
+                        config.net = self.net.clone(); // delay and timeouts stay our own
+                        self.config = config;
+                        self.seed = seed;
+                        self.peer = Some(from);
+                        self.start();
+                    },
+                    Err(e) => self.status = NetStatus::Failed(format!("the host's match config is unusable: {}", e))
+                }
+            },
+            Message::Input { ack, start, inputs } => {
+                if let Some(rollback) = self.rollback.as_mut() {
+                    rollback.add_remote_inputs(start, &inputs);
+                    self.remote_ack = self.remote_ack.max(ack);
+                }
+            },
+            Message::Checksum { frame, checksum } => {
+                if let Some(rollback) = self.rollback.as_mut() {
+                    rollback.add_remote_checksum(frame, checksum);
+                    if let Some(frame) = rollback.desync() {
+                        self.status = NetStatus::Desynced { frame };
+                    }
+                }
+            },
+            _ => return
+        }
+        self.last_heard = Instant::now();
+    }
+
+    fn start(&mut self) {
+        let net = &self.net;
+        self.rollback = Some(Rollback::new(self.local_side(), net.input_delay, net.max_rollback, net.checksum_interval));
+        self.status = NetStatus::Running;
+    }
+
+    // a lost datagram is fine, inputs go out again until acknowledged
+    fn send_to(&self, message : &Message, address : SocketAddr) {
+        self.socket.send_to(&message.encode(), address).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // one peer of a match played in this process
+    struct Peer {
+        net : NetSession,
+        sim : PongSim,
+        reach : f32,          // of the paddle's speed it uses, a slow one lets goals in
+        winner : Option<Side> // confirmed, the game would be over
+    }
+
+    impl Peer {
+        fn new(net : NetSession) -> Peer {
+            let sim = PongSim::with_seed(net.config().clone(), net.seed());
+            Peer { net, sim, reach : 1.0, winner : None }
+        }
+
+        // a frame of the game: what arrived, a step if the peer's inputs allow
+        // it and, unless held back, our inputs out; after the end it only
+        // keeps answering, like GameOver
+        fn update(&mut self, send : bool) {
+            self.net.receive();
+            if self.winner.is_none() {
+                let input = wobbly_follow(&self.sim, self.net.local_side(), self.net.frame()) * self.reach;
+                self.net.advance(&mut self.sim, input);
+                self.winner = self.net.confirmed_winner(&self.sim);
+            }
+            if send {
+                self.net.send();
+            }
+        }
+
+        // every step played on the other peer's real inputs
+        fn settled(&mut self) -> bool {
+            let rollback = self.net.rollback.as_mut().unwrap();
+            rollback.resync(&mut self.sim);
+            rollback.remote_frames() >= rollback.frame()
+        }
+    }
+
+    // keeps level with the ball, swaying off it every few steps so the
+    // other peer's guess of holding the last input keeps going wrong
+    fn wobbly_follow(sim : &PongSim, side : Side, frame : u32) -> f32 {
+        let paddle = match sim.paddle(side) {
+            Some(paddle) => paddle,
+            None => return 0.0
+        };
+        let target = sim.balls().first().map_or(50.0, |ball| ball.position[1]);
+        let sway = if (frame / 7).is_multiple_of(2) { 0.6 } else { -0.6 };
+        ((target - paddle.position[1]) / 3.0 + sway).clamp(-1.0, 1.0)
+    }
+
+    fn connect(config : PongConfig, seed : u64) -> (Peer, Peer) {
+        let mut host = NetSession::host(config.clone(), 0, seed).unwrap();
+        let port = host.local_address().unwrap().port();
+        let mut join = NetSession::join(config, &format!("127.0.0.1:{}", port)).unwrap();
+
+        for _ in 0..1000 {
+            join.send();
+            host.receive();
+            join.receive();
+            if *host.status() == NetStatus::Running && *join.status() == NetStatus::Running {
+                return (Peer::new(host), Peer::new(join));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!("the peers never connected: {:?}, {:?}", host.status(), join.status());
+    }
+
+    // the joiner goes quiet for 12 steps out of every 40, so the host runs
+    // ahead on guesses and has to rewind when the real inputs turn up
+    fn joiner_sends(iteration : u32) -> bool {
+        iteration % 40 >= 12
+    }
+
+    fn net_config() -> PongConfig {
+        let mut config = PongConfig::default();
+        config.net.input_delay = 0;
+        config.net.max_rollback = 30;
+        config.net.checksum_interval = 20;
+        config
+    }
+
+    #[test]
+    fn peers_agree_over_loopback() {
+        let (mut host, mut join) = connect(net_config(), 11);
+
+        let mut iteration = 0;
+        while host.net.frame() < 1200 || join.net.frame() < 1200 {
+            host.update(host.net.frame() < 1200 || iteration % 2 == 0);
+            if join.net.frame() < 1200 {
+                join.update(joiner_sends(iteration));
+            } else {
+                join.net.receive();
+                join.net.send();
+            }
+            iteration += 1;
+            assert!(iteration < 100_000, "stuck at frames {} and {}", host.net.frame(), join.net.frame());
+        }
+
+        // the last inputs and checksums still have to cross
+        for _ in 0..1000 {
+            host.net.receive();
+            join.net.receive();
+            if host.settled() && join.settled() && host.net.frame() == join.net.frame() {
+                break;
+            }
+            host.net.send();
+            join.net.send();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(*host.net.status(), NetStatus::Running);
+        assert_eq!(*join.net.status(), NetStatus::Running);
+        assert_eq!(host.net.frame(), join.net.frame());
+        assert_eq!(host.sim.checksum(), join.sim.checksum());
+        assert!(host.net.rewinds() > 0, "no prediction was ever wrong");
+    }
+
+    #[test]
+    fn match_ends_on_a_winner_both_peers_confirm() {
+        let mut config = net_config();
+        config.rules.points_to_win = 2;
+        config.rules.win_by_two = false;
+        let (mut host, mut join) = connect(config, 12);
+        join.reach = 0.3;
+
+        let mut iteration = 0;
+        while host.winner.is_none() || join.winner.is_none() {
+            host.update(true);
+            join.update(joiner_sends(iteration));
+
+            // a winner on a guess never ends the match
+            for peer in [&host, &join].iter() {
+                if let Some(winner) = peer.winner {
+                    assert_eq!(peer.sim.winner(), Some(winner));
+                }
+            }
+            iteration += 1;
+            assert!(iteration < 200_000, "no confirmed winner, {:?} and {:?}", host.sim.winner(), join.sim.winner());
+        }
+
+        assert_eq!(host.winner, join.winner);
+        assert_eq!(host.sim.checksum(), join.sim.checksum());
+        assert_eq!(host.sim.score(Side::Left), join.sim.score(Side::Left));
+        assert_eq!(host.sim.score(Side::Right), join.sim.score(Side::Right));
+    }
+
+    #[test]
+    fn a_peer_that_stops_sending_inputs_times_out() {
+        let mut config = net_config();
+        config.net.timeout = 0.2;
+        let (mut host, mut join) = connect(config, 13);
+
+        // the joiner still answers, but never steps, so its inputs run dry
+        let started = Instant::now();
+        while *host.net.status() == NetStatus::Running {
+            host.update(true);
+            join.net.receive();
+            join.net.send();
+            std::thread::sleep(Duration::from_millis(1));
+            assert!(started.elapsed() < Duration::from_secs(5), "the host never gave up waiting");
+        }
+        assert_eq!(*host.net.status(), NetStatus::Disconnected);
+    }
+}
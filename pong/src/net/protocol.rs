@@ -0,0 +1,90 @@
+// The messages two peers exchange, in a small little-endian byte layout.
+// Every datagram starts with the protocol version so an old build talking
+// to a new one drops the packets instead of misreading them.
+
+pub const PROTOCOL_VERSION : u8 = 4;
+
+// more unacknowledged inputs than this and the oldest are left for the next packet
+pub const MAX_INPUTS_PER_PACKET : usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Hello,                                   // joiner asking for a match
+    Welcome { seed : u64, config : String }, // host's answer, config as RON
+    Input { ack : u32, start : u32, inputs : Vec<f32> }, // sender's inputs from frame start on, ack = frames of ours it has
+    Checksum { frame : u32, checksum : u64 } // sim checksum at the start of frame
+}
+
+const HELLO : u8 = 0;
+const WELCOME : u8 = 1;
+const INPUT : u8 = 2;
+const CHECKSUM : u8 = 3;
+
+impl Message {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![PROTOCOL_VERSION];
+
+        match self {
+            Message::Hello => {
+                bytes.push(HELLO);
+            },
+            Message::Welcome { seed, config } => {
+                bytes.push(WELCOME);
+                bytes.extend_from_slice(&seed.to_le_bytes());
+                bytes.extend_from_slice(config.as_bytes());
+            },
+            Message::Input { ack, start, inputs } => {
+                bytes.push(INPUT);
+                bytes.extend_from_slice(&ack.to_le_bytes());
+                bytes.extend_from_slice(&start.to_le_bytes());
+                for input in inputs.iter().take(MAX_INPUTS_PER_PACKET) {
+                    bytes.extend_from_slice(&input.to_bits().to_le_bytes());
+                }
+            },
+            Message::Checksum { frame, checksum } => {
+                bytes.push(CHECKSUM);
+                bytes.extend_from_slice(&frame.to_le_bytes());
+                bytes.extend_from_slice(&checksum.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    // None for anything malformed or from another protocol version
+    pub fn decode(bytes : &[u8]) -> Option<Message> {
+        if bytes.len() < 2 || bytes[0] != PROTOCOL_VERSION {
+            return None;
+        }
+        let body = &bytes[2..];
+
+        match bytes[1] {
+            HELLO => Some(Message::Hello),
+            WELCOME if body.len() >= 8 => {
+                let config = String::from_utf8(body[8..].to_vec()).ok()?;
+                Some(Message::Welcome { seed : read_u64(&body[0..8]), config })
+            },
+            INPUT if body.len() >= 8 && (body.len() - 8).is_multiple_of(4) => {
+                let inputs = body[8..].chunks(4)
+                    .map(|chunk| f32::from_bits(read_u32(chunk)))
+                    .collect();
+                Some(Message::Input { ack : read_u32(&body[0..4]), start : read_u32(&body[4..8]), inputs })
+            },
+            CHECKSUM if body.len() == 12 => {
+                Some(Message::Checksum { frame : read_u32(&body[0..4]), checksum : read_u64(&body[4..12]) })
+            },
+            _ => None
+        }
+    }
+}
+
+fn read_u32(bytes : &[u8]) -> u32 {
+    let mut buffer = [0; 4];
+    buffer.copy_from_slice(bytes);
+    u32::from_le_bytes(buffer)
+}
+
+fn read_u64(bytes : &[u8]) -> u64 {
+    let mut buffer = [0; 8];
+    buffer.copy_from_slice(bytes);
+    u64::from_le_bytes(buffer)
+}
@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::sim::{Inputs, PongSim, Side};
+
+// Rollback over a PongSim. Local inputs are played input_delay steps after
+// they're made; the other peer's inputs are predicted by holding its last
+// known one until the real ones arrive, and a wrong guess rewinds to the
+// snapshot from before it and replays every step since with what was sent.
+#[derive(Debug, Clone)]
+pub struct Rollback {
+    local_side : Side,
+    max_rollback : u32,
+    checksum_interval : u32,
+    frame : u32,                    // steps the sim has taken
+    local_inputs : Vec<f32>,        // by frame, runs input_delay ahead of frame
+    remote_inputs : Vec<f32>,       // by frame, only what has arrived without gaps
+    rewind_to : Option<u32>,        // first frame played on a wrong prediction
+    snapshots : VecDeque<PongSim>,  // the sim at the start of every frame from snapshot_base on
+    snapshot_base : u32,
+    next_checksum : u32,
+    local_checksums : BTreeMap<u32, u64>,
+    remote_checksums : BTreeMap<u32, u64>,
+    outgoing_checksums : Vec<(u32, u64)>,
+    desync : Option<u32>,
+    winner_frame : Option<u32>,     // first frame the sim had a winner, maybe on a wrong prediction
+    rewinds : u32
+}
+
+impl Rollback {
+    pub fn new(local_side : Side, input_delay : u32, max_rollback : u32, checksum_interval : u32) -> Rollback {
+        Rollback {
+            local_side,
+            max_rollback,
+            checksum_interval,
+            frame : 0,
+            local_inputs : vec![0.0; input_delay as usize], // nobody moves before the delay is up
+            remote_inputs : Vec::new(),
+            rewind_to : None,
+            snapshots : VecDeque::new(),
+            snapshot_base : 0,
+            next_checksum : checksum_interval,
+            local_checksums : BTreeMap::new(),
+            remote_checksums : BTreeMap::new(),
+            outgoing_checksums : Vec::new(),
+            desync : None,
+            winner_frame : None,
+            rewinds : 0
+        }
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn local_inputs(&self) -> &[f32] {
+        &self.local_inputs
+    }
+
+    // frames of the other peer's inputs we have, counted from the first
+    pub fn remote_frames(&self) -> u32 {
+        self.remote_inputs.len() as u32
+    }
+
+    // first checksummed frame the two sims disagreed on
+    pub fn desync(&self) -> Option<u32> {
+        self.desync
+    }
+
+    // times a wrong prediction was rewound
+    pub fn rewinds(&self) -> u32 {
+        self.rewinds
+    }
+
+    // the sim's winner once every step up to the one that decided it was
+    // played on the other peer's real inputs; before that it's only a guess
+    // a late input can still take back
+    pub fn confirmed_winner(&self, sim : &PongSim) -> Option<Side> {
+        let frame = self.winner_frame?;
+        let settled = self.remote_frames() >= frame && self.rewind_to.is_none_or(|rewind| rewind >= frame);
+        if settled {
+            sim.winner()
+        } else {
+            None
+        }
+    }
+
+    // the other peer's inputs from frame start on; anything after a gap is
+    // dropped, the peer sends it again until we acknowledge it
+    pub fn add_remote_inputs(&mut self, start : u32, inputs : &[f32]) {
+        let known = self.remote_frames();
+        if start > known {
+            return;
+        }
+
+        let prediction = self.prediction();
+        for (frame, input) in (start..).zip(inputs.iter()) {
+            if frame < known {
+                continue;
+            }
+            if frame < self.frame && *input != prediction {
+                self.rewind_to.get_or_insert(frame);
+            }
+            self.remote_inputs.push(*input);
+        }
+    }
+
+    pub fn add_remote_checksum(&mut self, frame : u32, checksum : u64) {
+        self.remote_checksums.insert(frame, checksum);
+        self.compare_checksums();
+    }
+
+    // checksums made since the last call, for sending
+    pub fn take_checksums(&mut self) -> Vec<(u32, u64)> {
+        self.outgoing_checksums.drain(..).collect()
+    }
+
+    // replays from the first wrong prediction, if there was one
+    pub fn resync(&mut self, sim : &mut PongSim) {
+        if let Some(frame) = self.rewind_to.take() {
+            let target = self.frame;
+            let index = (frame - self.snapshot_base) as usize;
+
+            *sim = self.snapshots[index].clone();
+            self.snapshots.truncate(index);
+            self.frame = frame;
+            self.winner_frame = self.winner_frame.filter(|won| *won <= frame);
+            self.rewinds += 1;
+            while self.frame < target {
+                self.step(sim);
+            }
+        }
+        self.confirm(sim);
+    }
+
+    // steps the sim once, with local_input queued input_delay steps out;
+    // false when it's already max_rollback steps ahead of the other peer
+    pub fn advance(&mut self, sim : &mut PongSim, local_input : f32) -> bool {
+        self.resync(sim);
+        if self.frame >= self.remote_frames() + self.max_rollback {
+            return false;
+        }
+
+        self.local_inputs.push(local_input);
+        self.step(sim);
+        self.confirm(sim);
+        true
+    }
+
+    fn step(&mut self, sim : &mut PongSim) {
+        let frame = self.frame as usize;
+        let mut inputs = Inputs::default();
+        inputs.set(self.local_side, self.local_inputs[frame]);
+        inputs.set(self.local_side.opponent(), self.remote_inputs.get(frame).copied().unwrap_or(self.prediction()));
+
+        self.snapshots.push_back(sim.clone());
+        let dt = 1.0 / sim.config().tick_rate;
+        sim.step(&inputs, dt);
+        self.frame += 1;
+        if self.winner_frame.is_none() && sim.winner().is_some() {
+            self.winner_frame = Some(self.frame);
+        }
+    }
+
+    fn prediction(&self) -> f32 {
+        self.remote_inputs.last().copied().unwrap_or(0.0)
+    }
+
+    // frames played on real inputs from both peers can't be rewound any more,
+    // checksum the ones due and let their snapshots go
+    fn confirm(&mut self, sim : &PongSim) {
+        let confirmed = self.frame.min(self.remote_frames());
+
+        while self.next_checksum <= confirmed {
+            let frame = self.next_checksum;
+            let checksum = if frame == self.frame {
+                sim.checksum()
+            } else {
+                self.snapshots[(frame - self.snapshot_base) as usize].checksum()
+            };
+
+            self.local_checksums.insert(frame, checksum);
+            self.outgoing_checksums.push((frame, checksum));
+            self.next_checksum += self.checksum_interval;
+        }
+        self.compare_checksums();
+
+        while self.snapshot_base < confirmed {
+            self.snapshots.pop_front();
+            self.snapshot_base += 1;
+        }
+    }
+
+    fn compare_checksums(&mut self) {
+        let frames : Vec<u32> = self.remote_checksums.keys()
+            .filter(|frame| self.local_checksums.contains_key(frame))
+            .cloned()
+            .collect();
+
+        for frame in frames {
+            if self.local_checksums[&frame] != self.remote_checksums[&frame] && self.desync.is_none() {
+                self.desync = Some(frame);
+            }
+            self.remote_checksums.remove(&frame);
+
+            // older ones lost their partner to a dropped packet
+            self.local_checksums = self.local_checksums.split_off(&(frame + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PongConfig;
+
+    fn follow(sim : &PongSim, side : Side) -> f32 {
+        match (sim.paddle(side), sim.balls().first()) {
+            (Some(paddle), Some(ball)) => ((ball.position[1] - paddle.position[1]) / 3.0).clamp(-1.0, 1.0),
+            _ => 0.0
+        }
+    }
+
+    // the host never hears from the right paddle and guesses it stands
+    // still, which loses the point; the real inputs save it
+    #[test]
+    fn winner_on_a_wrong_guess_is_not_confirmed_and_is_taken_back() {
+        let mut config = PongConfig::default();
+        config.rules.points_to_win = 1;
+        config.rules.win_by_two = false;
+        let dt = 1.0 / config.tick_rate;
+
+        for seed in 0..50 {
+            let mut real = PongSim::with_seed(config.clone(), seed);
+            let mut played = Vec::new();
+            for _ in 0..1200 {
+                let mut inputs = Inputs::default();
+                inputs.set(Side::Left, follow(&real, Side::Left));
+                inputs.set(Side::Right, follow(&real, Side::Right));
+                real.step(&inputs, dt);
+                played.push(inputs);
+            }
+            if real.winner().is_some() {
+                continue;
+            }
+
+            let mut sim = PongSim::with_seed(config.clone(), seed);
+            let mut rollback = Rollback::new(Side::Left, 0, 1200, 60);
+            for inputs in played.iter() {
+                rollback.advance(&mut sim, inputs.get(Side::Left));
+            }
+            if sim.winner().is_none() {
+                continue;
+            }
+            assert_eq!(rollback.confirmed_winner(&sim), None);
+
+            let right : Vec<f32> = played.iter().map(|inputs| inputs.get(Side::Right)).collect();
+            rollback.add_remote_inputs(0, &right);
+            rollback.resync(&mut sim);
+            assert_eq!(sim.winner(), None);
+            assert_eq!(rollback.confirmed_winner(&sim), None);
+            assert_eq!(sim.checksum(), real.checksum());
+            assert_eq!(rollback.rewinds(), 1);
+            return;
+        }
+        panic!("no seed had a guess lose a point the real inputs save");
+    }
+
+    #[test]
+    fn winner_is_confirmed_once_the_deciding_inputs_arrive() {
+        let mut config = PongConfig::default();
+        config.rules.points_to_win = 1;
+        config.rules.win_by_two = false;
+
+        // neither paddle moves, someone concedes soon enough
+        let mut sim = PongSim::with_seed(config, 3);
+        let mut rollback = Rollback::new(Side::Left, 0, 2000, 60);
+        while sim.winner().is_none() {
+            assert!(rollback.advance(&mut sim, 0.0));
+        }
+        let decided = rollback.frame();
+        assert_eq!(rollback.confirmed_winner(&sim), None);
+
+        rollback.add_remote_inputs(0, &vec![0.0; decided as usize - 1]);
+        rollback.resync(&mut sim);
+        assert_eq!(rollback.confirmed_winner(&sim), None);
+
+        rollback.add_remote_inputs(decided - 1, &[0.0]);
+        rollback.resync(&mut sim);
+        assert_eq!(rollback.confirmed_winner(&sim), sim.winner());
+        assert!(sim.winner().is_some());
+    }
+}
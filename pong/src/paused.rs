@@ -0,0 +1,61 @@
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode},
+    prelude::*,
+    ui::{UiButton, UiEvent, UiEventType}
+};
+
+use crate::menu::{create_backdrop, create_button, delete_button, is_clicked, load_font};
+use crate::play::restart_match;
+
+// pushed on top of Pong, which stops dispatching the gameplay systems
+// until this state pops, so the ball resumes exactly where it was
+#[derive(Default)]
+pub struct Paused {
+    backdrop : Option<Entity>,
+    resume : Option<UiButton>,
+    restart : Option<UiButton>,
+    quit : Option<UiButton>
+}
+
+impl SimpleState for Paused {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        let font = load_font(world);
+
+        self.backdrop = Some(create_backdrop(world));
+        self.resume = Some(create_button(world, font.clone(), "Resume", 60.0));
+        self.restart = Some(create_button(world, font.clone(), "Restart", 0.0));
+        self.quit = Some(create_button(world, font, "Quit", -60.0));
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        if let Some(backdrop) = self.backdrop.take() {
+            data.world.delete_entity(backdrop).ok();
+        }
+        for button in [self.resume.take(), self.restart.take(), self.quit.take()].iter().flatten() {
+            delete_button(data.world, button);
+        }
+    }
+
+    fn handle_event(&mut self, data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Window(event) if is_key_down(event, VirtualKeyCode::Escape) => Trans::Pop,
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "pause" => Trans::Pop,
+            StateEvent::Ui(UiEvent { event_type : UiEventType::Click, target }) => {
+                if is_clicked(&self.resume, *target) {
+                    Trans::Pop
+                } else if is_clicked(&self.restart, *target) {
+                    restart_match(data.world);
+                    Trans::Pop
+                } else if is_clicked(&self.quit, *target) {
+                    Trans::Quit
+                } else {
+                    Trans::None
+                }
+            },
+            _ => Trans::None
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,659 @@
+use amethyst::{
+    assets::{AssetStorage, Loader, Handle},
+    core::{math::Vector3, transform::Transform, ArcThreadPool},
+    prelude::*,
+    ecs::prelude::{Component, DenseVecStorage, Dispatcher, DispatcherBuilder, Entity, Join},
+    input::InputEvent,
+    renderer::{palette::Srgba, resources::Tint, Camera, ImageFormat, SpriteRender, SpriteSheet, SpriteSheetFormat, Texture},
+    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    utils::application_root_dir
+};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::audio::{apply_volume, initialize_audio, save_audio_config};
+use crate::config::{AudioConfig, PongConfig};
+use crate::controller::{self, keyboard_axis, Keyboard, PaddleController};
+use crate::game_over::GameOver;
+use crate::history::{MatchHistory, MatchRecord};
+use crate::level::LevelList;
+use crate::main_menu::MainMenu;
+use crate::net::{NetSession, NetStatus};
+use crate::paused::Paused;
+use crate::replay::{Replay, ReplayPlayer};
+use crate::sim::{Inputs, PongSim, Side, SIDES};
+use crate::stats::MatchStats;
+use crate::systems;
+
+// size of the paddle and ball sprites in the sheet, entities are scaled from these
+pub const PADDLE_SPRITE_SIZE : [f32; 2] = [4.0, 16.0];
+pub const BALL_SPRITE_SIZE : f32 = 4.0;
+
+// pixels across the arena the score layout is made for, the window size in display.ron
+pub const LAYOUT_SIZE : f32 = 500.0;
+
+// indexed by Side::index
+#[derive(Default)]
+pub struct ScoreBoard {
+    pub scores : [u32; 4],
+    pub sets : [u32; 4],
+    pub lives : [u32; 4] // four-player matches only
+}
+
+// current rally, for the UI to show
+#[derive(Default)]
+pub struct Rally {
+    pub hits : u32,
+    pub speed : f32
+}
+
+// how far the frame is between the last sim step and the next, 0.0 - 1.0
+#[derive(Default)]
+pub struct Interpolation {
+    pub alpha : f32
+}
+
+// one text per side in the match
+pub struct ScoreText {
+    pub texts : Vec<(Side, Entity)>
+}
+
+// transient texts over the arena, animated by BannerSystem
+pub struct Banners {
+    pub headline : Entity,   // goals, sets and the match
+    pub countdown : Entity,  // before each serve
+    pub match_point : Entity,
+    pub replay : Entity      // the playback speed while watching a replay
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    #[default]
+    VsAi,
+    TwoPlayer,
+    FourPlayer, // Top and Bottom join, the computer plays the four_player ai_sides
+    Practice,   // endless rally against the computer
+    Online,   // against another peer through the NetSession resource
+    Replay    // watching the ReplayPlayer resource's recording
+}
+
+impl GameMode {
+    // the match config for this mode: who gets the AI and which rules apply
+    pub fn configure(self, config : &PongConfig) -> PongConfig {
+        let mut config = config.clone();
+        let ai_side = config.ai.side.unwrap_or(Side::Right);
+
+        // a replay keeps the recorded four_player, the others choose it
+        if self != GameMode::Replay {
+            config.four_player.enabled = self == GameMode::FourPlayer;
+        }
+
+        match self {
+            GameMode::VsAi => {
+                config.ai.side = Some(ai_side);
+            },
+            // the sim never reads the ai settings, so a replay without them plays the same
+            GameMode::TwoPlayer | GameMode::FourPlayer | GameMode::Online | GameMode::Replay => {
+                config.ai.side = None;
+            },
+            GameMode::Practice => {
+                config.ai.side = Some(ai_side);
+                config.rules.endless = true;
+            }
+        }
+        config
+    }
+}
+
+pub struct PongSprites {
+    pub sheet : Handle<SpriteSheet>
+}
+
+// marks the entity showing the sim paddle of the same side
+pub struct Paddle {
+    pub side : Side
+}
+
+impl Component for Paddle {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// what moves the paddle on the same entity, asked by PaddleSystem every frame
+pub struct Controlled {
+    pub controller : Box<dyn PaddleController>
+}
+
+impl Component for Controlled {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// marks the entity showing the sim ball with the same id
+pub struct Ball {
+    pub id : u32
+}
+
+impl Component for Ball {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// marks the entity showing the sim pickup with the same id
+pub struct PowerUp {
+    pub id : u32
+}
+
+impl Component for PowerUp {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// marks the entity showing the sim obstacle with the same id
+pub struct Obstacle {
+    pub id : u32
+}
+
+impl Component for Obstacle {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// gameplay systems live in the state's own dispatcher, so they only run while
+// Pong is on top of the state stack and freeze under Paused or GameOver
+#[derive(Default)]
+pub struct Pong<'a, 'b> {
+    dispatcher : Option<Dispatcher<'a, 'b>>,
+    sprite_sheet_handle : Option<Handle<SpriteSheet>>,
+    entities : Vec<Entity> // camera, bars, paddles and texts made in on_start
+}
+
+impl<'a, 'b> SimpleState for Pong<'a, 'b> {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) { // called once on state start
+        let world = data.world;
+
+        let mut dispatcher = DispatcherBuilder::new()
+            .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
+            .with(systems::PaddleSystem, "paddle_system", &[])
+            .with(systems::SimulationSystem::default(), "simulation_system", &["paddle_system"])
+            .with(systems::PongEventSystem, "pong_event_system", &["simulation_system"])
+            .with(systems::SyncSystem, "sync_system", &["simulation_system"])
+            .with(systems::PowerUpSystem, "power_up_system", &["simulation_system"])
+            .with(systems::ObstacleSystem, "obstacle_system", &["simulation_system"])
+            .with(systems::WinnerSystem, "winner_system", &["pong_event_system"])
+            .with(systems::SoundSystem::default(), "sound_system", &["pong_event_system"])
+            .with(systems::StatsSystem::default(), "stats_system", &["pong_event_system"])
+            .with(systems::BannerSystem::default(), "banner_system", &["pong_event_system"])
+            .build();
+        dispatcher.setup(world);
+        self.dispatcher = Some(dispatcher);
+
+        initialize_audio(world);
+
+        let mode = *world.read_resource::<GameMode>();
+        let sim = match mode {
+            GameMode::Online => {
+                let net = world.read_resource::<NetSession>(); // both peers play the host's config and seed
+                PongSim::with_seed(mode.configure(net.config()), net.seed())
+            },
+            GameMode::Replay => replay_start(world.read_resource::<ReplayPlayer>().replay()),
+            _ => {
+                reload_level(world, mode);
+                PongSim::with_seed(mode.configure(&world.read_resource::<PongConfig>()), rand::random())
+            }
+        };
+        start_recording(world, mode, &sim);
+        world.insert(sim); // the sim serves the first ball on its own
+        world.insert(Inputs::default());
+        start_stats(world);
+
+        self.sprite_sheet_handle.replace(load_sprite_sheet(world));
+        world.insert(PongSprites { sheet : self.sprite_sheet_handle.clone().unwrap() }); // balls are spawned by SyncSystem
+
+        self.entities.extend(initialize_paddles(world, self.sprite_sheet_handle.clone().unwrap()));
+        self.entities.extend(initialize_camera(world, self.sprite_sheet_handle.clone().unwrap()));
+        self.entities.extend(initialize_scoreboard(world));
+        self.entities.extend(initialize_banners(world));
+    }
+
+    // a match quit halfway is still worth a replay; everything shown goes
+    // with the state, so the next match starts on an empty world
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        save_recording(world);
+
+        // balls, pickups and obstacles come and go with the sim, made by its systems
+        let spawned : Vec<Entity> = {
+            let entities = world.entities();
+            let balls = world.read_storage::<Ball>();
+            let power_ups = world.read_storage::<PowerUp>();
+            let obstacles = world.read_storage::<Obstacle>();
+            (&entities, &balls).join().map(|(entity, _)| entity)
+                .chain((&entities, &power_ups).join().map(|(entity, _)| entity))
+                .chain((&entities, &obstacles).join().map(|(entity, _)| entity))
+                .collect()
+        };
+        world.delete_entities(&spawned).ok();
+        world.delete_entities(&self.entities).ok();
+        self.entities.clear();
+
+        world.remove::<ScoreText>();
+        world.remove::<Banners>();
+    }
+
+    fn handle_event(&mut self, data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        let mode = *data.world.read_resource::<GameMode>();
+
+        match &event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) if mode == GameMode::Replay => {
+                if action == "rematch" {
+                    restart_match(data.world);
+                    return Trans::None;
+                }
+
+                let mut player = data.world.write_resource::<ReplayPlayer>();
+                match action.as_str() {
+                    "pause" => player.paused = !player.paused,
+                    "replay_faster" => player.faster(),
+                    "replay_slower" => player.slower(),
+                    _ => {}
+                }
+                Trans::None
+            },
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "mute" => {
+                {
+                    let mut audio = data.world.write_resource::<AudioConfig>();
+                    audio.muted = !audio.muted;
+                }
+                apply_volume(data.world);
+                save_audio_config(data.world);
+                Trans::None
+            },
+            // the other peer can't be paused, so neither can we
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "pause" && mode != GameMode::Online => {
+                Trans::Push(Box::new(Paused::default()))
+            },
+            _ => Trans::None
+        }
+    }
+
+    fn update(&mut self, data : &mut StateData<'_, GameData<'_, '_ >>) -> SimpleTrans {
+        if let Some(dispatcher) = self.dispatcher.as_mut() {
+            dispatcher.dispatch(data.world);
+        }
+
+        let net_status = data.world.try_fetch::<NetSession>().map(|net| (net.status().clone(), net.frame(), net.rewinds()));
+        match net_status {
+            Some((NetStatus::Running, _, _)) | None => {},
+            Some((status, frame, rewinds)) => {
+                eprintln!("online match ended: {:?} at frame {}, after {} rollbacks", status, frame, rewinds);
+                data.world.remove::<NetSession>();
+                return Trans::Switch(Box::new(MainMenu::default()));
+            }
+        }
+
+        // online, a winner seen on a predicted input isn't final until the
+        // other peer's real inputs agree, the sim plays on until they do
+        let winner = {
+            let sim = data.world.read_resource::<PongSim>();
+            match data.world.try_fetch::<NetSession>() {
+                Some(net) => net.confirmed_winner(&sim),
+                None => sim.winner()
+            }
+        };
+        match winner {
+            Some(winner) => Trans::Push(Box::new(GameOver::new(winner))),
+            None => Trans::None
+        }
+    }
+}
+
+// fresh match in the same arena, used by rematch and restart; a replay starts over instead
+pub fn restart_match(world : &mut World) {
+    save_recording(world);
+
+    let mode = *world.read_resource::<GameMode>();
+    let replay = world.try_fetch_mut::<ReplayPlayer>().map(|mut player| {
+        player.rewind();
+        replay_start(player.replay())
+    });
+    match replay {
+        Some(sim) if mode == GameMode::Replay => *world.write_resource::<PongSim>() = sim,
+        _ if mode == GameMode::Online => world.write_resource::<PongSim>().reset(),
+        _ => {
+            reload_level(world, mode);
+            let config = mode.configure(&world.read_resource::<PongConfig>());
+            world.write_resource::<PongSim>().reset_with(config);
+        }
+    }
+
+    let sim = (*world.read_resource::<PongSim>()).clone();
+    start_recording(world, mode, &sim);
+    start_stats(world);
+    *world.write_resource::<ScoreBoard>() = ScoreBoard::default(); // WinnerSystem puts the texts back
+}
+
+// reads the level picked in the settings from disk again, keeping the one
+// loaded before when the file no longer loads
+fn reload_level(world : &mut World, mode : GameMode) {
+    let levels = match world.try_fetch::<LevelList>() {
+        Some(levels) => levels.clone(),
+        None => return
+    };
+    let loaded = levels.load(&mode.configure(&world.read_resource::<PongConfig>()));
+    match loaded {
+        Ok(level) => world.write_resource::<PongConfig>().level = level,
+        Err(e) => eprintln!("keeping the current level, {} failed: {}", levels.label(), e)
+    }
+}
+
+fn replay_start(replay : &Replay) -> PongSim {
+    PongSim::with_seed(GameMode::Replay.configure(&replay.config), replay.seed)
+}
+
+// online matches aren't recorded, rollback replays steps the recording would have to undo
+fn start_recording(world : &mut World, mode : GameMode, sim : &PongSim) {
+    match mode {
+        GameMode::Online | GameMode::Replay => {},
+        _ => world.insert(Replay::record(sim))
+    }
+}
+
+// writes the match recorded so far to replays/, once
+pub fn save_recording(world : &mut World) {
+    let mut replay = match world.remove::<Replay>() {
+        Some(replay) if replay.steps() > 0 => replay,
+        _ => return
+    };
+    replay.finish(&world.read_resource::<PongSim>());
+
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    let saved = application_root_dir()
+        .map(|root| root.join("replays").join(format!("{}.ron", seconds)))
+        .map_err(|e| e.to_string())
+        .and_then(|path| replay.save(&path).map(|_| path).map_err(|e| e.to_string()));
+    match saved {
+        Ok(path) => println!("Replay saved to {}", path.display()),
+        Err(e) => eprintln!("could not save replay: {}", e)
+    }
+}
+
+pub fn history_path() -> Result<PathBuf, String> {
+    application_root_dir()
+        .map(|root| root.join("data").join("history.ron"))
+        .map_err(|e| e.to_string())
+}
+
+// adds the finished match to the history, replays were already counted when they were played
+pub fn record_match(world : &mut World) {
+    let mode = *world.read_resource::<GameMode>();
+    if mode == GameMode::Replay {
+        return;
+    }
+
+    let players = player_names(world);
+    let record = {
+        let sim = world.read_resource::<PongSim>();
+        let sides = sim.match_sides();
+
+        MatchRecord {
+            timestamp : SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+            mode : format!("{:?}", mode),
+            sides : sides.to_vec(),
+            players,
+            scores : sides.iter().map(|side| sim.score(*side)).collect(),
+            sets : sides.iter().map(|side| sim.sets(*side)).collect(),
+            winner : sim.winner(),
+            longest_rally : sim.longest_rally(),
+            duration : sim.elapsed()
+        }
+    };
+
+    let saved = history_path().and_then(|path| {
+        let mut history = MatchHistory::load_or_recover(&path);
+        history.matches.push(record);
+        history.save(&path).map_err(|e| e.to_string())
+    });
+    if let Err(e) = saved {
+        eprintln!("could not save match history: {}", e);
+    }
+}
+
+// who plays each of the match's sides, for the history and the stats
+fn player_names(world : &World) -> Vec<String> {
+    let sim = world.read_resource::<PongSim>();
+    let config = world.read_resource::<PongConfig>();
+    let local = world.try_fetch::<NetSession>().map(|net| net.local_side());
+
+    sim.match_sides().iter()
+        .map(|side| match local {
+            // online, this machine's player goes by the first name in its config
+            Some(local) if *side == local => config.players.left.clone(),
+            Some(_) => "Online".to_string(),
+            None => sim.config().controller(*side).describe()
+                .unwrap_or_else(|| config.players.name(*side).to_string())
+        })
+        .collect()
+}
+
+fn start_stats(world : &mut World) {
+    let mode = *world.read_resource::<GameMode>();
+    let sides = world.read_resource::<PongSim>().match_sides();
+    let stats = MatchStats::new(format!("{:?}", mode), sides, player_names(world));
+    world.insert(stats);
+}
+
+// writes the finished match's stats to stats/, as <timestamp>.json and a row
+// of matches.csv; replays were already written when they were played
+pub fn save_stats(world : &mut World) {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    world.write_resource::<MatchStats>().finish(&world.read_resource::<PongSim>(), timestamp);
+    if *world.read_resource::<GameMode>() == GameMode::Replay {
+        return;
+    }
+
+    let stats = world.read_resource::<MatchStats>();
+    let saved = application_root_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|root| {
+            let dir = root.join("stats");
+            stats.save_json(dir.join(format!("{}.json", timestamp)))
+                .and_then(|_| stats.append_csv(dir.join("matches.csv")))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = saved {
+        eprintln!("{}", e);
+    }
+}
+
+pub fn set_score_text<F : Fn(Side) -> String>(world : &mut World, label : F) {
+    let score_text = world.read_resource::<ScoreText>();
+    let mut ui_text = world.write_storage::<UiText>();
+
+    for (side, entity) in score_text.texts.iter() {
+        if let Some(text) = ui_text.get_mut(*entity) {
+            text.text = label(*side);
+        }
+    }
+}
+
+// ViewportSystem fits the camera to the window, whatever its shape
+fn initialize_camera(world : &mut World, sprite_sheet_handle : Handle<SpriteSheet>) -> Vec<Entity> {
+    let (arena_width, arena_height) = {
+        let config = world.read_resource::<PongConfig>();
+        (config.arena.width, config.arena.height)
+    };
+
+    let mut transform = Transform::default(); // get new tranform component
+    transform.set_translation_xyz(arena_width * 0.5, arena_height * 0.5, 1.0); // create pos for camera
+
+    let mut created = vec![world
+        .create_entity()
+        .with(Camera::standard_2d(arena_width, arena_height)) // create camera component pointed to Z axis
+        .with(transform) // center it on the arena
+        .build()];
+
+    // grey bars over everything outside the arena, long enough for any
+    // window, so letterboxing and pillarboxing show where the walls are
+    let reach = (arena_width + arena_height) * 10.0;
+    for side in SIDES.iter() {
+        let across = side.across();
+        let arena = [arena_width, arena_height];
+
+        let mut center = [arena_width * 0.5, arena_height * 0.5];
+        center[across] = if side.facing() > 0.0 { -reach * 0.5 } else { arena[across] + reach * 0.5 };
+        let mut size = [reach * 2.0, reach * 2.0];
+        size[across] = reach;
+
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(center[0], center[1], 0.0);
+        transform.set_scale(Vector3::new(size[0] / PADDLE_SPRITE_SIZE[0], size[1] / PADDLE_SPRITE_SIZE[1], 1.0));
+
+        created.push(world
+            .create_entity()
+            .with(transform)
+            .with(SpriteRender {
+                sprite_sheet : sprite_sheet_handle.clone(),
+                sprite_number : 0
+            })
+            .with(Tint(Srgba::new(0.12, 0.12, 0.12, 1.0)))
+            .build());
+    }
+    created
+}
+
+fn initialize_paddles(world : &mut World, sprite_sheet_handle : Handle<SpriteSheet>) -> Vec<Entity> {
+    let (config, paddles) = {
+        let sim = world.read_resource::<PongSim>();
+        (sim.config().clone(), sim.paddles().to_vec())
+    };
+
+    let sprite_render = SpriteRender {
+        sprite_sheet : sprite_sheet_handle,
+        sprite_number : 0
+    };
+
+    let mode = *world.read_resource::<GameMode>();
+    let replays = application_root_dir().map(|root| root.join("replays")).unwrap_or_default();
+    let mut created = Vec::new();
+    for state in paddles {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(state.position[0], state.position[1], 0.0);
+
+        let mut paddle = world
+            .create_entity()
+            .with(Paddle { side : state.side })
+            .with(transform)
+            .with(sprite_render.clone());
+
+        // a replay moves every paddle from its recording
+        if mode != GameMode::Replay {
+            let kind = config.controller(state.side);
+            let controller = controller::create(&kind, state.side, &config, rand::random(), &replays)
+                .unwrap_or_else(|e| {
+                    eprintln!("{:?} paddle falls back to the keyboard, {}", state.side, e);
+                    Box::new(Keyboard { axis : keyboard_axis(state.side).to_string() })
+                });
+            paddle = paddle.with(Controlled { controller });
+        }
+
+        created.push(paddle.build());
+    }
+    created
+}
+
+fn load_sprite_sheet(world : &mut World) -> Handle<SpriteSheet> {
+    let texture_handle = {
+        let loader = world.read_resource::<Loader>();
+        let texture_storage = world.read_resource::<AssetStorage<Texture>>();
+        loader.load(
+            "texture/pong_spritesheet.png",
+            ImageFormat::default(),
+            (),
+            &texture_storage
+        )
+    };
+
+    let loader = world.read_resource::<Loader>();
+    let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
+    loader.load(
+        "texture/pong_sprite_sheet.ron",
+        SpriteSheetFormat(texture_handle),
+        (),
+        &sprite_sheet_store
+    )
+}
+
+fn initialize_scoreboard(world : &mut World) -> Vec<Entity> {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf", 
+        TtfFormat, 
+        (), 
+        &world.read_resource()
+    );
+    let sides = world.read_resource::<PongSim>().match_sides();
+
+    let mut texts = Vec::new();
+    for side in sides.iter() {
+        let (anchor, [x, y], [width, height], font_size) = score_layout(sides.len(), *side);
+
+        let transform = UiTransform::new(
+            format!("{:?}", side), anchor, anchor, // (ID, ORIGIN_X, ORIGIN_Y)
+            x, y, 1.0, width, height             // (x, y, z, w, h)
+        );
+        let text = world
+            .create_entity()
+            .with(transform)
+            .with(UiText::new(
+                font.clone(),
+                "0".to_string(),        // initial text
+                [1.0, 1.0, 1.0, 1.0],   // color
+                font_size
+            ))
+            .build();
+        texts.push((*side, text));
+    }
+
+    let created = texts.iter().map(|(_, text)| *text).collect();
+    world.insert(ScoreText { texts });
+    created
+}
+
+// where a side's score text goes in a LAYOUT_SIZE window showing just the
+// arena: its anchor, offset, size and font size; ViewportSystem moves and
+// scales it to follow the arena in the actual window. Two players keep their
+// scores side by side at the top, with four every side's lives sit by its
+// own goal
+pub fn score_layout(players : usize, side : Side) -> (Anchor, [f32; 2], [f32; 2], f32) {
+    let (anchor, x, y, font_size) = match (players, side) {
+        (2, Side::Left) => (Anchor::TopMiddle, 50.0, -50.0, 50.0),
+        (2, _) => (Anchor::TopMiddle, -50.0, -50.0, 50.0),
+        (_, Side::Left) => (Anchor::MiddleLeft, 20.0, 0.0, 30.0),
+        (_, Side::Right) => (Anchor::MiddleRight, -20.0, 0.0, 30.0),
+        (_, Side::Top) => (Anchor::TopMiddle, 0.0, -20.0, 30.0),
+        (_, Side::Bottom) => (Anchor::BottomMiddle, 0.0, 20.0, 30.0)
+    };
+    let width = if players == 2 { 200.0 } else { 60.0 };
+
+    (anchor, [x, y], [width, 50.0], font_size)
+}
+
+fn initialize_banners(world : &mut World) -> Vec<Entity> {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource()
+    );
+
+    // all start invisible, BannerSystem fades them in when there's something to say
+    let mut banner = |id : &str, anchor : Anchor, y : f32, font_size : f32| {
+        world
+            .create_entity()
+            .with(UiTransform::new(id.to_string(), anchor, Anchor::Middle, 0.0, y, 2.0, 480.0, font_size * 1.6))
+            .with(UiText::new(font.clone(), String::new(), [1.0, 1.0, 1.0, 0.0], font_size))
+            .build()
+    };
+
+    let headline = banner("headline", Anchor::Middle, 80.0, 32.0);
+    let countdown = banner("countdown", Anchor::Middle, 0.0, 80.0);
+    let match_point = banner("match_point", Anchor::TopMiddle, -110.0, 20.0);
+    let replay = banner("replay", Anchor::BottomMiddle, 30.0, 20.0);
+
+    world.insert(Banners { headline, countdown, match_point, replay });
+    vec![headline, countdown, match_point, replay]
+}
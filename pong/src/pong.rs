@@ -1,227 +0,0 @@
-use amethyst::{
-    assets::{AssetStorage, Loader, Handle},
-    core::transform::Transform, 
-    core::timing::Time,
-    prelude::*,
-    ecs::prelude::{Component, DenseVecStorage, Entity},
-    renderer::{Camera, ImageFormat, SpriteRender, SpriteSheet, SpriteSheetFormat, Texture},
-    ui::{Anchor, TtfFormat, UiText, UiTransform}
-};
-
-
-#[derive(Default)]
-pub struct ScoreBoard {
-    pub score_left : u32,
-    pub score_right : u32
-}
-
-pub struct ScoreText {
-    pub p1_score : Entity,
-    pub p2_score : Entity
-}
-
-
-pub const PADDLE_WIDTH : f32 = 16.0;
-pub const PADDLE_HEIGHT : f32 = 4.0;
-
-#[derive(PartialEq, Eq)]
-pub enum Side {
-    Left, 
-    Right
-}
-
-pub struct Paddle {
-    pub side : Side, 
-    pub width : f32,
-    pub height : f32
-}
-
-impl Paddle {
-    fn new(side : Side) -> Paddle {
-        Paddle {
-            side : side,
-            width : PADDLE_WIDTH,
-            height : PADDLE_HEIGHT
-        }
-    }
-}
-
-impl Component for Paddle {
-    type Storage = DenseVecStorage<Self>;
-}
-
-pub const BALL_VELOCITY_X : f32 = 75.0;
-pub const BALL_VELOCITY_Y : f32 = 50.0;
-pub const BALL_RADIUS : f32 = 2.0;
-
-pub struct Ball {
-    pub velocity : [f32; 2],
-    pub radius : f32
-}
-
-impl Component for Ball {
-    type Storage = DenseVecStorage<Self>;
-}
-
-pub const ARENA_WIDTH : f32 = 100.0;
-pub const ARENA_HEIGHT : f32 = 100.0;
-
-#[derive(Default)]
-pub struct Pong {
-    ball_start_timer : Option<f32>, 
-    sprite_sheet_handle : Option<Handle<SpriteSheet>>
-}
-
-impl SimpleState for Pong {
-    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) { // called once on state start
-        let world = data.world;
-
-        self.ball_start_timer.replace(2.0);
-        self.sprite_sheet_handle.replace(load_sprite_sheet(world));
-
-        initialize_paddles(world, self.sprite_sheet_handle.clone().unwrap());
-        initialize_camera(world);
-        initialize_scoreboard(world);
-    }
-
-    fn update(&mut self, data : &mut StateData<'_, GameData<'_, '_ >>) -> SimpleTrans{
-        if let Some(mut timer) = self.ball_start_timer.take() {
-            {
-                let time = data.world.fetch::<Time>();
-                timer -= time.delta_seconds();
-            }
-            if timer <= 0.0 {
-                initialize_ball(data.world, self.sprite_sheet_handle.clone().unwrap());
-            } else {
-                self.ball_start_timer.replace(timer);
-            }
-        }
-        Trans::None
-    }
-}
-
-fn initialize_camera(world : &mut World) {
-    let mut transform = Transform::default(); // get new tranform component
-    transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 1.0); // create pos for camera
-
-    world
-        .create_entity()
-        .with(Camera::standard_2d(ARENA_WIDTH, ARENA_HEIGHT)) // create camera component pointed to Z axis
-        .with(transform) // center it on the arena
-        .build();
-}
-
-fn initialize_paddles(world : &mut World, sprite_sheet_handle : Handle<SpriteSheet>) {
-    let mut left_transform = Transform::default();
-    let mut right_transform = Transform::default();
-
-    let y = ARENA_HEIGHT / 2.0;
-    left_transform.set_translation_xyz(PADDLE_WIDTH * 0.5, y, 0.0);
-    right_transform.set_translation_xyz(ARENA_WIDTH - PADDLE_WIDTH * 0.5, y, 0.0);
-
-    let sprite_render = SpriteRender {
-        sprite_sheet : sprite_sheet_handle,
-        sprite_number : 0
-    };
-
-    world
-        .create_entity()
-        .with(Paddle::new(Side::Right))
-        .with(right_transform)
-        .with(sprite_render.clone())
-        .build();
-
-    world
-        .create_entity()
-        .with(Paddle::new(Side::Left))
-        .with(left_transform)
-        .with(sprite_render.clone())
-        .build();
-}
-
-fn initialize_ball(world : &mut World, sprite_handle : Handle<SpriteSheet>) {
-    let mut transform = Transform::default();
-    transform.set_translation_xyz(ARENA_WIDTH/2.0, ARENA_HEIGHT/2.0, 0.0);
-
-    let sprite_render = SpriteRender {
-        sprite_sheet : sprite_handle,
-        sprite_number : 1
-    };
-
-    world.create_entity()
-        .with(sprite_render)
-        .with(
-            Ball {
-                velocity : [BALL_VELOCITY_X, BALL_VELOCITY_Y],
-                radius : BALL_RADIUS
-            }
-        )
-        .with(transform)
-        .build();
-}
-
-
-fn load_sprite_sheet(world : &mut World) -> Handle<SpriteSheet> {
-    let texture_handle = {
-        let loader = world.read_resource::<Loader>();
-        let texture_storage = world.read_resource::<AssetStorage<Texture>>();
-        loader.load(
-            "texture/pong_spritesheet.png",
-            ImageFormat::default(),
-            (),
-            &texture_storage
-        )
-    };
-
-    let loader = world.read_resource::<Loader>();
-    let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
-    loader.load(
-        "texture/pong_sprite_sheet.ron",
-        SpriteSheetFormat(texture_handle),
-        (),
-        &sprite_sheet_store
-    )
-}
-
-fn initialize_scoreboard(world : &mut World) {
-    let font = world.read_resource::<Loader>().load(
-        "font/square.ttf", 
-        TtfFormat, 
-        (), 
-        &world.read_resource()
-    );
-
-    let p1_transform = UiTransform::new(
-        "P1".to_string(), Anchor::TopMiddle, Anchor::TopMiddle, // (ID, ORIGIN_X, ORIGIN_Y)
-        50.0, -50.0, 1.0, 200.0, 50.0                           // (x, y, y, w, h, tab-order)
-    );
-
-    let p2_transform = UiTransform::new(
-        "P2".to_string(), Anchor::TopMiddle, Anchor::TopMiddle, 
-        -50.0, -50.0, 1.0, 200.0, 50.0
-    );
-
-    let p1_score = world
-        .create_entity()
-        .with(p1_transform)
-        .with(UiText::new(
-            font.clone(), 
-            "0".to_string(),        // initial text
-            [1.0, 1.0, 1.0, 1.0],   // color
-            50.0                    // font size
-        ))
-        .build();
-    
-    let p2_score = world
-        .create_entity()
-        .with(p2_transform)
-        .with(UiText::new(
-            font.clone(),
-            "0".to_string(), 
-            [1.0,1.0,1.0,1.0],
-            50.0
-        ))
-        .build();
-
-    world.insert(ScoreText {p1_score, p2_score });
-}
\ No newline at end of file
@@ -0,0 +1,206 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::Path;
+
+use ron::de::from_reader;
+use ron::ser::to_string;
+use serde::{Deserialize, Serialize};
+
+use crate::config::PongConfig;
+use crate::sim::{Inputs, PongSim};
+
+// bumped whenever the sim changes in a way that plays old replays differently
+pub const REPLAY_VERSION : u32 = 3;
+
+pub const PLAYBACK_SPEEDS : [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+// A match as the sim saw it: the seed and config it started from and the
+// inputs of every step, which is all a deterministic sim needs to play it
+// again exactly. Steps with the same inputs are stored as one run.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Replay {
+    pub version : u32,
+    pub seed : u64,
+    pub config : PongConfig,
+    pub inputs : Vec<InputRun>,
+    pub scores : Vec<u32>, // where the match stood when the recording ended, one per side that played
+    pub sets : Vec<u32>
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InputRun {
+    pub steps : u32,
+    pub inputs : Inputs
+}
+
+impl Replay {
+    // starts recording a sim that hasn't stepped yet
+    pub fn record(sim : &PongSim) -> Replay {
+        Replay {
+            version : REPLAY_VERSION,
+            seed : sim.seed(),
+            config : sim.config().clone(),
+            inputs : Vec::new(),
+            scores : Vec::new(),
+            sets : Vec::new()
+        }
+    }
+
+    pub fn push(&mut self, inputs : &Inputs) {
+        match self.inputs.last_mut() {
+            Some(run) if run.inputs == *inputs => run.steps += 1,
+            _ => self.inputs.push(InputRun { steps : 1, inputs : *inputs })
+        }
+    }
+
+    // notes the score so far, for verify to check against
+    pub fn finish(&mut self, sim : &PongSim) {
+        self.scores = sim.match_sides().iter().map(|side| sim.score(*side)).collect();
+        self.sets = sim.match_sides().iter().map(|side| sim.sets(*side)).collect();
+    }
+
+    pub fn steps(&self) -> u32 {
+        self.inputs.iter().map(|run| run.steps).sum()
+    }
+
+    // a sim set up the way the recorded one started
+    pub fn start(&self) -> PongSim {
+        PongSim::with_seed(self.config.clone(), self.seed)
+    }
+
+    // plays the whole replay without drawing anything and checks it ends on the recorded score
+    pub fn verify(&self) -> Result<(), ReplayError> {
+        let mut sim = self.start();
+        let dt = 1.0 / self.config.tick_rate;
+        for run in self.inputs.iter() {
+            for _ in 0..run.steps {
+                sim.step(&run.inputs, dt);
+            }
+        }
+
+        let scores : Vec<u32> = sim.match_sides().iter().map(|side| sim.score(*side)).collect();
+        let sets : Vec<u32> = sim.match_sides().iter().map(|side| sim.sets(*side)).collect();
+        if scores == self.scores && sets == self.sets {
+            Ok(())
+        } else {
+            Err(ReplayError::Mismatch(format!(
+                "recorded {} (sets {}), replayed {} (sets {})",
+                dashed(&self.scores), dashed(&self.sets), dashed(&scores), dashed(&sets))))
+        }
+    }
+
+    pub fn load<P : AsRef<Path>>(path : P) -> Result<Replay, ReplayError> {
+        let file = File::open(path)?;
+        let replay : Replay = from_reader(file)
+            .map_err(|e| ReplayError::Parse(e.to_string()))?;
+
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(replay.version));
+        }
+        replay.config.validate()
+            .map_err(|e| ReplayError::Parse(e.to_string()))?;
+        Ok(replay)
+    }
+
+    pub fn save<P : AsRef<Path>>(&self, path : P) -> Result<(), ReplayError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let text = to_string(self)
+            .map_err(|e| ReplayError::Parse(e.to_string()))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+// hands out a replay's inputs one step at a time, for watching it back
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    replay : Replay,
+    run : usize,
+    run_step : u32, // steps played of the current run
+    speed : usize,  // index into PLAYBACK_SPEEDS
+    pub paused : bool
+}
+
+impl ReplayPlayer {
+    pub fn new(replay : Replay) -> ReplayPlayer {
+        ReplayPlayer {
+            replay,
+            run : 0,
+            run_step : 0,
+            speed : 2,
+            paused : false
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // inputs for the next step, None once the recording runs out
+    pub fn next_inputs(&mut self) -> Option<Inputs> {
+        while let Some(run) = self.replay.inputs.get(self.run) {
+            if self.run_step < run.steps {
+                self.run_step += 1;
+                return Some(run.inputs);
+            }
+            self.run += 1;
+            self.run_step = 0;
+        }
+        None
+    }
+
+    pub fn rewind(&mut self) {
+        self.run = 0;
+        self.run_step = 0;
+    }
+
+    // playback speed, 0.0 while paused
+    pub fn speed(&self) -> f32 {
+        if self.paused { 0.0 } else { PLAYBACK_SPEEDS[self.speed] }
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+}
+
+// scores the way they're read out, 11-9
+pub fn dashed(values : &[u32]) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<_>>().join("-")
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse(String),
+    Version(u32),
+    Mismatch(String)
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "could not read or write replay: {}", e),
+            ReplayError::Parse(e) => write!(f, "could not parse replay: {}", e),
+            ReplayError::Version(version) => write!(f, "replay is version {}, this build plays version {}", version, REPLAY_VERSION),
+            ReplayError::Mismatch(e) => write!(f, "replay does not reproduce its match: {}", e)
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(e : std::io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
@@ -0,0 +1,139 @@
+use amethyst::{
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{UiButton, UiEvent, UiEventType, UiText}
+};
+
+use crate::audio_settings::AudioSettings;
+use crate::config::{Difficulty, PongConfig};
+use crate::level::LevelList;
+use crate::menu::{create_button, delete_button, is_clicked, load_font};
+
+const POINTS_TO_WIN : [u32; 3] = [5, 11, 21];
+
+// tweaks the PongConfig resource for the next match, each button cycles its value
+#[derive(Default)]
+pub struct Settings {
+    difficulty : Option<UiButton>,
+    points : Option<UiButton>,
+    win_by_two : Option<UiButton>,
+    multiball : Option<UiButton>,
+    power_ups : Option<UiButton>,
+    level : Option<UiButton>,
+    audio : Option<UiButton>,
+    back : Option<UiButton>
+}
+
+impl Settings {
+    fn refresh_labels(&self, world : &mut World) {
+        let config = world.read_resource::<PongConfig>();
+        let level = world.try_fetch::<LevelList>().map_or("none".to_string(), |levels| levels.label());
+        let mut ui_text = world.write_storage::<UiText>();
+
+        let labels = vec![
+            (&self.difficulty, format!("AI: {:?}", config.ai.difficulty)),
+            (&self.points, format!("Points: {}", config.rules.points_to_win)),
+            (&self.win_by_two, format!("Win by two: {}", if config.rules.win_by_two { "on" } else { "off" })),
+            (&self.multiball, format!("Multiball: {}", if config.multiball.enabled { "on" } else { "off" })),
+            (&self.power_ups, format!("Power-ups: {}", if config.power_ups.enabled { "on" } else { "off" })),
+            (&self.level, format!("Level: {}", level))
+        ];
+        for (button, label) in labels {
+            if let Some(text) = button.as_ref().and_then(|button| ui_text.get_mut(button.text_entity)) {
+                text.text = label;
+            }
+        }
+    }
+}
+
+impl SimpleState for Settings {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        let font = load_font(world);
+
+        self.difficulty = Some(create_button(world, font.clone(), "", 196.0));
+        self.points = Some(create_button(world, font.clone(), "", 144.0));
+        self.win_by_two = Some(create_button(world, font.clone(), "", 92.0));
+        self.multiball = Some(create_button(world, font.clone(), "", 40.0));
+        self.power_ups = Some(create_button(world, font.clone(), "", -12.0));
+        self.level = Some(create_button(world, font.clone(), "", -64.0));
+        self.audio = Some(create_button(world, font.clone(), "Audio", -116.0));
+        self.back = Some(create_button(world, font, "Back", -168.0));
+        self.refresh_labels(world);
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let buttons = [self.difficulty.take(), self.points.take(), self.win_by_two.take(), self.multiball.take(), self.power_ups.take(), self.level.take(), self.audio.take(), self.back.take()];
+        for button in buttons.iter().flatten() {
+            delete_button(data.world, button);
+        }
+    }
+
+    // the audio screen draws over this one, hide ours meanwhile
+    fn on_pause(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        self.on_stop(data);
+    }
+
+    fn on_resume(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        self.on_start(data);
+    }
+
+    fn handle_event(&mut self, data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Window(event) if is_key_down(event, VirtualKeyCode::Escape) => Trans::Pop,
+            StateEvent::Ui(UiEvent { event_type : UiEventType::Click, target }) => {
+                if is_clicked(&self.back, *target) {
+                    return Trans::Pop;
+                }
+                if is_clicked(&self.audio, *target) {
+                    return Trans::Push(Box::new(AudioSettings::default()));
+                }
+                if is_clicked(&self.level, *target) {
+                    select_next_level(data.world);
+                }
+
+                {
+                    let mut config = data.world.write_resource::<PongConfig>();
+                    if is_clicked(&self.difficulty, *target) {
+                        config.ai.difficulty = match config.ai.difficulty {
+                            Difficulty::Easy => Difficulty::Normal,
+                            Difficulty::Normal => Difficulty::Hard,
+                            _ => Difficulty::Easy
+                        };
+                    } else if is_clicked(&self.points, *target) {
+                        let next = POINTS_TO_WIN.iter().position(|points| *points == config.rules.points_to_win)
+                            .map_or(0, |index| (index + 1) % POINTS_TO_WIN.len());
+                        config.rules.points_to_win = POINTS_TO_WIN[next];
+                    } else if is_clicked(&self.win_by_two, *target) {
+                        config.rules.win_by_two = !config.rules.win_by_two;
+                    } else if is_clicked(&self.multiball, *target) {
+                        config.multiball.enabled = !config.multiball.enabled;
+                    } else if is_clicked(&self.power_ups, *target) {
+                        config.power_ups.enabled = !config.power_ups.enabled;
+                    }
+                }
+                self.refresh_labels(data.world);
+                Trans::None
+            },
+            _ => Trans::None
+        }
+    }
+}
+
+// steps to the next level file and loads it now, so a broken file shows up
+// here rather than when the match starts
+fn select_next_level(world : &mut World) {
+    let levels = match world.try_fetch_mut::<LevelList>() {
+        Some(mut levels) => {
+            levels.select_next();
+            levels.clone()
+        },
+        None => return
+    };
+    let loaded = levels.load(&world.read_resource::<PongConfig>());
+    match loaded {
+        Ok(level) => world.write_resource::<PongConfig>().level = level,
+        Err(e) => eprintln!("could not use level {}: {}", levels.label(), e)
+    }
+}
@@ -0,0 +1,161 @@
+// Swept tests for the ball against the arena: instead of checking where the
+// ball ended up, they find the fraction of this step's motion at which it
+// first touches something, so a fast ball can't skip through a thin paddle.
+
+pub struct Hit {
+    pub time : f32,       // fraction of the motion, 0.0 - 1.0
+    pub normal : [f32; 2] // surface normal at the contact, pointing at the ball
+}
+
+// circle center moving by `motion` toward the line `position == line`, coming
+// from the side `normal` (1.0 or -1.0) points to; a center already past the
+// line while moving further out hits at time 0
+pub fn sweep_line(position : f32, motion : f32, line : f32, normal : f32) -> Option<f32> {
+    if motion * normal >= 0.0 {
+        return None;
+    }
+
+    let time = ((line - position) / motion).max(0.0);
+    if time <= 1.0 {
+        Some(time)
+    } else {
+        None
+    }
+}
+
+// circle of `radius` moving by `motion` against the box `min` - `max`, tested
+// as a ray against the box grown by the radius with rounded corners; a circle
+// that already overlaps the box is not a hit
+pub fn sweep_circle_rect(start : [f32; 2], motion : [f32; 2], radius : f32, min : [f32; 2], max : [f32; 2]) -> Option<Hit> {
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = [0.0, 0.0];
+
+    for axis in 0..2 {
+        let low = min[axis] - radius;
+        let high = max[axis] + radius;
+
+        if motion[axis] == 0.0 {
+            if start[axis] < low || start[axis] > high {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far, side) = if motion[axis] > 0.0 {
+            ((low - start[axis]) / motion[axis], (high - start[axis]) / motion[axis], -1.0)
+        } else {
+            ((high - start[axis]) / motion[axis], (low - start[axis]) / motion[axis], 1.0)
+        };
+
+        if near > entry {
+            entry = near;
+            normal = [0.0, 0.0];
+            normal[axis] = side;
+        }
+        exit = exit.min(far);
+    }
+
+    if !(0.0..=1.0).contains(&entry) || entry > exit {
+        return None;
+    }
+
+    let contact = [start[0] + motion[0] * entry, start[1] + motion[1] * entry];
+    let beside_x = contact[0] < min[0] || contact[0] > max[0];
+    let beside_y = contact[1] < min[1] || contact[1] > max[1];
+    if !(beside_x && beside_y) {
+        return Some(Hit { time : entry, normal });
+    }
+
+    // the grown box has rounded corners, so near one the circle may still miss
+    let corner = [
+        if contact[0] < min[0] { min[0] } else { max[0] },
+        if contact[1] < min[1] { min[1] } else { max[1] }
+    ];
+    sweep_circle_point(start, motion, radius, corner)
+}
+
+// circle of `radius` moving by `motion` against a point
+pub fn sweep_circle_point(start : [f32; 2], motion : [f32; 2], radius : f32, point : [f32; 2]) -> Option<Hit> {
+    let offset = [start[0] - point[0], start[1] - point[1]];
+    let a = motion[0] * motion[0] + motion[1] * motion[1];
+    let b = 2.0 * (offset[0] * motion[0] + offset[1] * motion[1]);
+    let c = offset[0] * offset[0] + offset[1] * offset[1] - radius * radius;
+
+    let discriminant = b * b - 4.0 * a * c;
+    if a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+    if !(0.0..=1.0).contains(&time) {
+        return None;
+    }
+
+    let contact = [start[0] + motion[0] * time, start[1] + motion[1] * time];
+    Some(Hit {
+        time,
+        normal : [(contact[0] - point[0]) / radius, (contact[1] - point[1]) / radius]
+    })
+}
+
+pub fn reflect(velocity : [f32; 2], normal : [f32; 2]) -> [f32; 2] {
+    let along = velocity[0] * normal[0] + velocity[1] * normal[1];
+    [velocity[0] - 2.0 * along * normal[0], velocity[1] - 2.0 * along * normal[1]]
+}
+
+pub fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
+    x >= left && x <= right && y >= bottom && y <= top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_is_hit_head_on() {
+        assert_eq!(sweep_line(10.0, -20.0, 2.0, 1.0), Some(0.4));
+        assert_eq!(sweep_line(10.0, -5.0, 2.0, 1.0), None);  // stops short
+        assert_eq!(sweep_line(10.0, 20.0, 2.0, 1.0), None);  // moving away
+        assert_eq!(sweep_line(1.0, -20.0, 2.0, 1.0), Some(0.0)); // already past it
+    }
+
+    #[test]
+    fn rect_is_hit_head_on() {
+        let hit = sweep_circle_rect([0.0, 5.0], [10.0, 0.0], 1.0, [5.0, 0.0], [6.0, 10.0]).unwrap();
+        assert!((hit.time - 0.4).abs() < 1e-6);
+        assert_eq!(hit.normal, [-1.0, 0.0]);
+    }
+
+    #[test]
+    fn fast_circle_hits_a_thin_rect() {
+        // ends well past the box, a check of the end position alone would miss it
+        let hit = sweep_circle_rect([-500.0, 5.0], [1000.0, 0.0], 1.0, [0.0, 0.0], [1.0, 10.0]).unwrap();
+        assert!((hit.time - 0.499).abs() < 1e-6);
+        assert_eq!(hit.normal, [-1.0, 0.0]);
+    }
+
+    #[test]
+    fn rounded_corner_is_missed() {
+        // crosses the grown box's square corner, but not the rounded one
+        assert!(sweep_circle_rect([-3.0, -0.3], [4.0, 4.0], 1.0, [0.0, 0.0], [1.0, 1.0]).is_none());
+        // a little closer in, it touches the corner point
+        let hit = sweep_circle_rect([-3.0, -0.7], [4.0, 4.0], 1.0, [0.0, 0.0], [1.0, 1.0]).unwrap();
+        let length = (hit.normal[0] * hit.normal[0] + hit.normal[1] * hit.normal[1]).sqrt();
+        assert!((length - 1.0).abs() < 1e-4);
+        assert!(hit.normal[0] < 0.0 && hit.normal[1] > 0.0);
+    }
+
+    #[test]
+    fn start_inside_is_not_a_hit() {
+        assert!(sweep_circle_rect([0.5, 0.5], [3.0, 0.0], 1.0, [0.0, 0.0], [1.0, 1.0]).is_none());
+        assert!(sweep_circle_point([0.5, 0.0], [3.0, 0.0], 1.0, [0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn zero_motion_is_not_a_hit() {
+        assert!(sweep_circle_rect([-1.5, 0.5], [0.0, 0.0], 1.0, [0.0, 0.0], [1.0, 1.0]).is_none());
+        assert!(sweep_circle_point([-1.5, 0.0], [0.0, 0.0], 1.0, [0.0, 0.0]).is_none());
+        assert_eq!(sweep_line(5.0, 0.0, 2.0, 1.0), None);
+    }
+}
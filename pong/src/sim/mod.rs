@@ -0,0 +1,1151 @@
+// Engine independent pong rules: no amethyst types in here, so the whole
+// match can be stepped headlessly. Only plain f32 math in a fixed order, so
+// the same inputs and dt always give bit-identical states.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PongConfig;
+use crate::level::Shape;
+use self::collision::{point_in_rect, reflect, sweep_circle_rect, sweep_line, Hit};
+use self::power_ups::EffectSummary;
+
+pub use self::obstacles::ObstacleState;
+pub use self::power_ups::{ActiveEffect, PickupState};
+pub use self::rng::SimRng;
+
+mod collision;
+mod obstacles;
+mod power_ups;
+mod rng;
+
+const MAX_BOUNCES_PER_STEP : usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom
+}
+
+// every side there is, Left and Right first since they play every match
+pub const SIDES : [Side; 4] = [Side::Left, Side::Right, Side::Top, Side::Bottom];
+
+impl Side {
+    pub fn index(self) -> usize {
+        match self {
+            Side::Left => 0,
+            Side::Right => 1,
+            Side::Top => 2,
+            Side::Bottom => 3
+        }
+    }
+
+    // the side across the arena
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top
+        }
+    }
+
+    // axis from this side's goal into the arena, 0 for x and 1 for y
+    pub fn across(self) -> usize {
+        match self {
+            Side::Left | Side::Right => 0,
+            Side::Top | Side::Bottom => 1
+        }
+    }
+
+    // axis the paddle moves along, parallel to its goal line
+    pub fn along(self) -> usize {
+        1 - self.across()
+    }
+
+    // direction on the across axis from this side's goal into the arena
+    pub fn facing(self) -> f32 {
+        match self {
+            Side::Left | Side::Bottom => 1.0,
+            Side::Right | Side::Top => -1.0
+        }
+    }
+}
+
+// gameplay moments, collected while stepping until take_events hands them out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PongEvent {
+    PaddleHit { side : Side, speed : f32, offset : f32 }, // speed the ball leaves with; where it hit, -1.0 - 1.0 from end to end
+    PaddleGraze { side : Side }, // off a paddle's end or back, no return, so not part of the rally
+    WallHit,   // walls and level blocks
+    BumperHit,
+    Goal { scorer : Option<Side>, conceder : Side }, // four players: no scorer unless another paddle hit it last
+    Eliminated { side : Side },
+    Serve,
+    MatchOver { winner : Side }
+}
+
+// axis values for each paddle, in -1.0 - 1.0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct Inputs {
+    axes : [f32; 4]
+}
+
+impl Inputs {
+    pub fn get(&self, side : Side) -> f32 {
+        self.axes[side.index()]
+    }
+
+    pub fn set(&mut self, side : Side, value : f32) {
+        self.axes[side.index()] = value;
+    }
+}
+
+// Left and Right paddles stand upright and move in y, Top and Bottom lie flat
+// and move in x; size is in arena axes, so a flat paddle is wider than tall
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaddleState {
+    pub side : Side,
+    pub position : [f32; 2],
+    pub previous_position : [f32; 2], // before the last step, for drawing between steps
+    pub velocity : f32,               // along its goal line
+    pub size : [f32; 2]
+}
+
+impl PaddleState {
+    // where to draw the paddle `alpha` of the way from the last step to the next
+    pub fn interpolated_position(&self, alpha : f32) -> [f32; 2] {
+        [
+            self.previous_position[0] + (self.position[0] - self.previous_position[0]) * alpha,
+            self.previous_position[1] + (self.position[1] - self.previous_position[1]) * alpha
+        ]
+    }
+
+    pub fn bounds(&self) -> ([f32; 2], [f32; 2]) {
+        (
+            [self.position[0] - self.size[0] * 0.5, self.position[1] - self.size[1] * 0.5],
+            [self.position[0] + self.size[0] * 0.5, self.position[1] + self.size[1] * 0.5]
+        )
+    }
+
+    // extent along the goal line
+    pub fn length(&self) -> f32 {
+        self.size[self.side.along()]
+    }
+
+    pub fn thickness(&self) -> f32 {
+        self.size[self.side.across()]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallState {
+    pub id : u32,
+    pub position : [f32; 2],
+    pub previous_position : [f32; 2], // before the last step, for drawing between steps
+    pub velocity : [f32; 2],
+    pub radius : f32,
+    pub last_hit : Option<Side>, // paddle that touched it last, collects the pickups it passes
+    pub stuck : Option<Stuck>
+}
+
+// the ball waiting at the center to be served
+#[derive(Debug, Clone, PartialEq)]
+struct Serve {
+    remaining : f32,
+    toward : Side,
+    ball_id : u32
+}
+
+// a ball held by a sticky paddle, it leaves with the velocity it was given on the hit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stuck {
+    pub side : Side,
+    pub offset : f32, // along the paddle from its center
+    pub remaining : f32
+}
+
+impl BallState {
+    pub fn interpolated_position(&self, alpha : f32) -> [f32; 2] {
+        [
+            self.previous_position[0] + (self.position[0] - self.previous_position[0]) * alpha,
+            self.previous_position[1] + (self.position[1] - self.previous_position[1]) * alpha
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PongSim {
+    config : PongConfig,
+    seed : u64,
+    rng : SimRng,
+    paddles : Vec<PaddleState>,
+    balls : Vec<BallState>,
+    sides : Vec<Side>, // still defending a goal, the edges of the rest are walls
+    scores : [u32; 4],
+    sets : [u32; 4],
+    conceded : [u32; 4],
+    winner : Option<Side>,
+    rally : u32, // paddle hits since the last point
+    round_goals : u32,
+    multiball_timer : f32,
+    milestone_rally : u32, // rally count that spawned the last extra ball
+    serve : Option<Serve>,
+    last_conceder : Option<Side>,
+    next_ball_id : u32,
+    pickups : Vec<PickupState>,
+    effects : Vec<ActiveEffect>,
+    effect_summary : EffectSummary,
+    pickup_timer : f32,
+    next_pickup_id : u32,
+    obstacles : Vec<ObstacleState>,
+    next_obstacle_id : u32,
+    events : Vec<PongEvent>,
+    elapsed : f32, // seconds of play, stops at the match's end
+    steps : u64,   // step calls so far, the match's end doesn't stop it
+    longest_rally : u32
+}
+
+impl PongSim {
+    pub fn new(config : PongConfig) -> PongSim {
+        PongSim::with_seed(config, 0)
+    }
+
+    // everything random in a match comes from the seed, so the same seed,
+    // config and inputs always play out the same
+    pub fn with_seed(config : PongConfig, seed : u64) -> PongSim {
+        let sides = match_sides(&config).to_vec();
+        let paddles = sides.iter().map(|side| new_paddle(*side, &config)).collect();
+
+        let mut sim = PongSim {
+            config,
+            seed,
+            rng : SimRng::new(seed),
+            paddles,
+            balls : Vec::new(),
+            sides,
+            scores : [0; 4],
+            sets : [0; 4],
+            conceded : [0; 4],
+            winner : None,
+            rally : 0,
+            round_goals : 0,
+            multiball_timer : 0.0,
+            milestone_rally : 0,
+            serve : None,
+            last_conceder : None,
+            next_ball_id : 0,
+            pickups : Vec::new(),
+            effects : Vec::new(),
+            effect_summary : EffectSummary::default(),
+            pickup_timer : 0.0,
+            next_pickup_id : 0,
+            obstacles : Vec::new(),
+            next_obstacle_id : 0,
+            events : Vec::new(),
+            elapsed : 0.0,
+            steps : 0,
+            longest_rally : 0
+        };
+
+        sim.spawn_obstacles();
+
+        // nobody has conceded before the first serve, toss for it
+        let toward = sim.sides[sim.rng.below(sim.sides.len() as u32) as usize];
+        sim.begin_serve(toward);
+        sim
+    }
+
+    pub fn config(&self) -> &PongConfig {
+        &self.config
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn paddles(&self) -> &[PaddleState] {
+        &self.paddles
+    }
+
+    pub fn paddle(&self, side : Side) -> Option<&PaddleState> {
+        self.paddles.iter().find(|paddle| paddle.side == side)
+    }
+
+    pub fn balls(&self) -> &[BallState] {
+        &self.balls
+    }
+
+    pub fn ball(&self, id : u32) -> Option<&BallState> {
+        self.balls.iter().find(|ball| ball.id == id)
+    }
+
+    pub fn score(&self, side : Side) -> u32 {
+        self.scores[side.index()]
+    }
+
+    pub fn sets(&self, side : Side) -> u32 {
+        self.sets[side.index()]
+    }
+
+    pub fn winner(&self) -> Option<Side> {
+        self.winner
+    }
+
+    // every side that started the match
+    pub fn match_sides(&self) -> &'static [Side] {
+        match_sides(&self.config)
+    }
+
+    // the sides not yet eliminated
+    pub fn sides(&self) -> &[Side] {
+        &self.sides
+    }
+
+    // goals this side can still concede in a four-player match
+    pub fn lives(&self, side : Side) -> u32 {
+        self.config.four_player.lives.saturating_sub(self.conceded[side.index()])
+    }
+
+    // one more point for this side would win it the match
+    pub fn match_point(&self, side : Side) -> bool {
+        self.winner.is_none()
+            && !self.config.four_player.enabled
+            && self.next_point_wins_set(side)
+            && self.sets[side.index()] + 1 >= self.config.rules.sets_to_win()
+    }
+
+    pub fn rally(&self) -> u32 {
+        self.rally
+    }
+
+    pub fn longest_rally(&self) -> u32 {
+        self.longest_rally.max(self.rally)
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    // how many steps were taken, the index of the next one's inputs in a recording
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    // seconds until the waiting ball is served, None while it's in play
+    pub fn serve_countdown(&self) -> Option<f32> {
+        self.serve.as_ref().map(|serve| serve.remaining)
+    }
+
+    pub fn pickups(&self) -> &[PickupState] {
+        &self.pickups
+    }
+
+    pub fn effects(&self) -> &[ActiveEffect] {
+        &self.effects
+    }
+
+    pub fn obstacles(&self) -> &[ObstacleState] {
+        &self.obstacles
+    }
+
+    // events since the last call; a rollback that rewinds past events already
+    // taken plays them again, so listeners must cope with the odd repeat
+    pub fn take_events(&mut self) -> Vec<PongEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // speed of the fastest ball in play, 0.0 while waiting for a serve
+    pub fn ball_speed(&self) -> f32 {
+        self.balls.iter()
+            .map(|ball| length(ball.velocity))
+            .fold(0.0, f32::max)
+    }
+
+    // fingerprint of everything that steers the match, two sims that played
+    // the same steps from the same seed agree on it; used to catch desyncs
+    pub fn checksum(&self) -> u64 {
+        let mut values = vec![
+            self.rng.state(),
+            self.sides.len() as u64,
+            self.rally as u64,
+            self.serve.as_ref().map_or(u64::MAX, |serve| serve.remaining.to_bits() as u64),
+            self.pickups.len() as u64,
+            self.effects.len() as u64
+        ];
+        for side in SIDES.iter() {
+            let index = side.index();
+            values.extend(vec![self.scores[index] as u64, self.sets[index] as u64, self.conceded[index] as u64]);
+        }
+        for paddle in self.paddles.iter() {
+            let along = paddle.side.along();
+            values.extend(vec![paddle.position[along].to_bits() as u64, paddle.size[along].to_bits() as u64]);
+        }
+        for obstacle in self.obstacles.iter() {
+            values.extend(vec![obstacle.position[0].to_bits() as u64, obstacle.position[1].to_bits() as u64]);
+        }
+        for ball in self.balls.iter() {
+            values.extend(vec![
+                ball.id as u64,
+                ball.position[0].to_bits() as u64, ball.position[1].to_bits() as u64,
+                ball.velocity[0].to_bits() as u64, ball.velocity[1].to_bits() as u64
+            ]);
+        }
+
+        // FNV-1a, one whole value at a time
+        values.iter().fold(0xcbf2_9ce4_8422_2325, |hash, value| (hash ^ value).wrapping_mul(0x100_0000_01b3))
+    }
+
+    // back to a fresh match with the same config and a seed drawn from this
+    // one, ids keep counting so nothing showing an old ball or pickup
+    // mistakes it for a new one
+    pub fn reset(&mut self) {
+        let config = self.config.clone();
+        self.reset_with(config);
+    }
+
+    // a fresh match like reset, on a config changed between matches
+    pub fn reset_with(&mut self, config : PongConfig) {
+        let next_ball_id = self.next_ball_id;
+        let next_pickup_id = self.next_pickup_id;
+        let next_obstacle_id = self.next_obstacle_id;
+        let seed = self.rng.next_u64();
+
+        *self = PongSim::with_seed(config, seed);
+        for ball in self.balls.iter_mut() {
+            ball.id += next_ball_id;
+        }
+        if let Some(serve) = self.serve.as_mut() {
+            serve.ball_id += next_ball_id;
+        }
+        self.next_ball_id += next_ball_id;
+        self.next_pickup_id = next_pickup_id;
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.id += next_obstacle_id;
+        }
+        self.next_obstacle_id += next_obstacle_id;
+    }
+
+    pub fn step(&mut self, inputs : &Inputs, dt : f32) {
+        self.steps += 1;
+        for paddle in self.paddles.iter_mut() {
+            paddle.previous_position = paddle.position;
+        }
+        for ball in self.balls.iter_mut() {
+            ball.previous_position = ball.position;
+        }
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.previous_position = obstacle.position;
+        }
+
+        if self.winner.is_some() {
+            return;
+        }
+        self.elapsed += dt;
+
+        self.update_power_ups(dt);
+        self.move_paddles(inputs, dt);
+        self.move_obstacles(dt);
+        self.serve(dt);
+        self.spawn_extra_balls(dt);
+        self.move_balls(dt);
+        self.collide_balls();
+        self.collect_power_ups();
+        self.score_goals();
+    }
+
+    fn move_paddles(&mut self, inputs : &Inputs, dt : f32) {
+        let speed = self.config.paddles.speed;
+
+        for paddle in self.paddles.iter_mut() {
+            let along = paddle.side.along();
+            let previous = paddle.position[along];
+            let mut movement = inputs.get(paddle.side).clamp(-1.0, 1.0);
+            if self.effect_summary.reversed[paddle.side.index()] {
+                movement = -movement;
+            }
+            if movement != 0.0 {
+                paddle.position[along] += speed * movement * dt;
+                keep_in_lane(paddle, &self.config);
+            }
+            paddle.velocity = if dt > 0.0 { (paddle.position[along] - previous) / dt } else { 0.0 };
+        }
+    }
+
+    // a fresh ball waits at the center until the serve delay is up
+    fn begin_serve(&mut self, toward : Side) {
+        let arena = &self.config.arena;
+        let center = [arena.width / 2.0, arena.height / 2.0];
+
+        self.balls.clear();
+        self.balls.push(BallState {
+            id : self.next_ball_id,
+            position : center,
+            previous_position : center,
+            velocity : [0.0, 0.0],
+            radius : self.config.ball.radius,
+            last_hit : None,
+            stuck : None
+        });
+        self.serve = Some(Serve {
+            remaining : self.config.serve.delay,
+            toward,
+            ball_id : self.next_ball_id
+        });
+        self.next_ball_id += 1;
+    }
+
+    fn serve(&mut self, dt : f32) {
+        let mut serve = match self.serve.take() {
+            Some(serve) => serve,
+            None => return
+        };
+        serve.remaining -= dt;
+        if serve.remaining > 0.0 {
+            self.serve = Some(serve);
+            return;
+        }
+
+        let max_angle = self.config.serve.max_angle.to_radians();
+        let angle = self.rng.range(-max_angle, max_angle);
+        let speed = self.config.ball.base_speed();
+        let toward = serve.toward;
+
+        if let Some(ball) = self.balls.iter_mut().find(|ball| ball.id == serve.ball_id) {
+            // from the center toward that side's goal
+            ball.velocity[toward.across()] = -toward.facing() * speed * angle.cos();
+            ball.velocity[toward.along()] = speed * angle.sin();
+            self.events.push(PongEvent::Serve);
+        }
+    }
+
+    fn spawn_ball(&mut self, mirror_x : bool, mirror_y : bool) {
+        let arena = &self.config.arena;
+        let ball = &self.config.ball;
+        let flip = |mirror : bool| if mirror { -1.0 } else { 1.0 };
+
+        self.balls.push(BallState {
+            id : self.next_ball_id,
+            position : [arena.width / 2.0, arena.height / 2.0],
+            previous_position : [arena.width / 2.0, arena.height / 2.0],
+            velocity : [ball.velocity_x * flip(mirror_x), ball.velocity_y * flip(mirror_y)],
+            radius : ball.radius,
+            last_hit : None,
+            stuck : None
+        });
+        self.next_ball_id += 1;
+    }
+
+    // multiball: more balls join a running round on a timer and at rally milestones
+    fn spawn_extra_balls(&mut self, dt : f32) {
+        let multiball = &self.config.multiball;
+        if !multiball.enabled || self.balls.is_empty() || self.serve.is_some() {
+            return;
+        }
+
+        self.multiball_timer += dt;
+        let timer_due = multiball.spawn_interval > 0.0 && self.multiball_timer >= multiball.spawn_interval;
+        let milestone_due = multiball.spawn_every_hits > 0 && self.rally >= self.milestone_rally + multiball.spawn_every_hits;
+
+        if (timer_due || milestone_due) && (self.balls.len() as u32) < multiball.max_balls {
+            // alternate the directions so extra balls fan out from the center
+            let count = self.balls.len();
+            self.spawn_ball(count % 2 == 1, count % 4 >= 2);
+        }
+        if timer_due {
+            self.multiball_timer = 0.0;
+        }
+        if milestone_due {
+            self.milestone_rally = self.rally;
+        }
+    }
+
+    // moves every ball through this step's motion, bouncing off walls and
+    // paddles at the exact time of impact and spending the rest of the
+    // motion in the new direction
+    fn move_balls(&mut self, dt : f32) {
+        let config = &self.config;
+        let effects = &self.effect_summary;
+        let sides = &self.sides;
+        let events = &mut self.events;
+        let extent = arena_extent(config);
+
+        // the edges nobody defends, Top and Bottom in a two-player match
+        let walls : Vec<Side> = SIDES.iter()
+            .cloned()
+            .filter(|side| !sides.contains(side))
+            .collect();
+
+        for ball in self.balls.iter_mut() {
+            if let Some(stuck) = ball.stuck.as_mut() {
+                stuck.remaining -= dt;
+                if let Some(paddle) = self.paddles.iter().find(|paddle| paddle.side == stuck.side) {
+                    let side = paddle.side;
+                    ball.position[side.across()] = paddle.position[side.across()] + side.facing() * (paddle.thickness() * 0.5 + ball.radius);
+                    ball.position[side.along()] = paddle.position[side.along()] + stuck.offset;
+                }
+                if stuck.remaining > 0.0 {
+                    continue;
+                }
+                ball.stuck = None;
+            }
+
+            // a paddle moved onto the ball, send it back the way it came
+            for paddle in self.paddles.iter() {
+                let (min, max) = paddle.bounds();
+                if moving_to_goal(ball, paddle) && point_in_rect(
+                    ball.position[0], ball.position[1],
+                    min[0] - ball.radius, min[1] - ball.radius,
+                    max[0] + ball.radius, max[1] + ball.radius) {
+                    hit_paddle(ball, paddle, config, effects);
+                    self.rally += 1;
+                    events.push(PongEvent::PaddleHit { side : paddle.side, speed : length(ball.velocity), offset : hit_offset(ball, paddle) });
+                }
+            }
+
+            // so did a moving obstacle, send the ball off it
+            for obstacle in self.obstacles.iter() {
+                if let Some(normal) = obstacle.push_out(ball) {
+                    let closing = (ball.velocity[0] - obstacle.velocity[0]) * normal[0] + (ball.velocity[1] - obstacle.velocity[1]) * normal[1];
+                    if closing < 0.0 {
+                        obstacle.bounce(ball, normal, config.ball.max_speed);
+                        events.push(obstacle_event(obstacle));
+                    }
+                }
+            }
+
+            let mut remaining = dt;
+            for _ in 0..MAX_BOUNCES_PER_STEP {
+                if ball.stuck.is_some() {
+                    break;
+                }
+
+                let speed_factor = effects.ball_speed_factor;
+                let motion = [ball.velocity[0] * remaining * speed_factor, ball.velocity[1] * remaining * speed_factor];
+                let mut first : Option<(Hit, Contact)> = None;
+
+                for wall in walls.iter() {
+                    let across = wall.across();
+                    let line = goal_line(*wall, extent, ball.radius);
+                    if let Some(time) = sweep_line(ball.position[across], motion[across], line, wall.facing()) {
+                        if first.as_ref().is_none_or(|(hit, _)| time < hit.time) {
+                            let mut normal = [0.0, 0.0];
+                            normal[across] = wall.facing();
+                            first = Some((Hit { time, normal }, Contact::Wall));
+                        }
+                    }
+                }
+
+                for paddle in self.paddles.iter() {
+                    let (min, max) = paddle.bounds();
+                    if let Some(hit) = sweep_circle_rect(ball.position, motion, ball.radius, min, max) {
+                        if first.as_ref().is_none_or(|(first, _)| hit.time < first.time) {
+                            first = Some((hit, Contact::Paddle(paddle)));
+                        }
+                    }
+                }
+
+                for obstacle in self.obstacles.iter() {
+                    if let Some(hit) = obstacle.sweep(ball, motion) {
+                        if first.as_ref().is_none_or(|(first, _)| hit.time < first.time) {
+                            first = Some((hit, Contact::Obstacle(obstacle)));
+                        }
+                    }
+                }
+
+                let (hit, contact) = match first {
+                    Some(first) => first,
+                    None => {
+                        ball.position[0] += motion[0];
+                        ball.position[1] += motion[1];
+                        break;
+                    }
+                };
+
+                ball.position[0] += motion[0] * hit.time;
+                ball.position[1] += motion[1] * hit.time;
+                remaining -= remaining * hit.time;
+
+                match contact {
+                    // the face toward the arena sends the ball back at an angle
+                    Contact::Paddle(paddle) if hit.normal[paddle.side.across()] * paddle.side.facing() > 0.0 && moving_to_goal(ball, paddle) => {
+                        hit_paddle(ball, paddle, config, effects);
+                        self.rally += 1;
+                        events.push(PongEvent::PaddleHit { side : paddle.side, speed : length(ball.velocity), offset : hit_offset(ball, paddle) });
+                    },
+                    Contact::Paddle(paddle) => {
+                        ball.velocity = reflect(ball.velocity, hit.normal);
+                        events.push(PongEvent::PaddleGraze { side : paddle.side });
+                    },
+                    Contact::Obstacle(obstacle) => {
+                        obstacle.bounce(ball, hit.normal, config.ball.max_speed);
+                        events.push(obstacle_event(obstacle));
+                    },
+                    Contact::Wall => {
+                        ball.velocity = reflect(ball.velocity, hit.normal);
+                        events.push(PongEvent::WallHit);
+                    }
+                }
+            }
+        }
+    }
+
+    // equal density balls bounce off each other elastically
+    fn collide_balls(&mut self) {
+        for i in 0..self.balls.len() {
+            let (head, tail) = self.balls.split_at_mut(i + 1);
+            let first = &mut head[i];
+
+            for second in tail.iter_mut() {
+                let delta = [second.position[0] - first.position[0], second.position[1] - first.position[1]];
+                let distance = length(delta);
+                let reach = first.radius + second.radius;
+                if distance >= reach || distance == 0.0 {
+                    continue;
+                }
+
+                let normal = [delta[0] / distance, delta[1] / distance];
+                let first_mass = first.radius * first.radius;
+                let second_mass = second.radius * second.radius;
+                let total_mass = first_mass + second_mass;
+
+                // push them apart so they don't collide again next step
+                let overlap = reach - distance;
+                let positions = first.position.iter_mut().zip(second.position.iter_mut());
+                for ((first_axis, second_axis), normal_axis) in positions.zip(normal.iter()) {
+                    *first_axis -= normal_axis * overlap * second_mass / total_mass;
+                    *second_axis += normal_axis * overlap * first_mass / total_mass;
+                }
+
+                let closing = (first.velocity[0] - second.velocity[0]) * normal[0]
+                    + (first.velocity[1] - second.velocity[1]) * normal[1];
+                if closing <= 0.0 {
+                    continue;
+                }
+
+                let velocities = first.velocity.iter_mut().zip(second.velocity.iter_mut());
+                for ((first_axis, second_axis), normal_axis) in velocities.zip(normal.iter()) {
+                    *first_axis -= 2.0 * second_mass / total_mass * closing * normal_axis;
+                    *second_axis += 2.0 * first_mass / total_mass * closing * normal_axis;
+                }
+            }
+        }
+    }
+
+    fn score_goals(&mut self) {
+        let extent = arena_extent(&self.config);
+        let multiball = self.config.multiball.enabled;
+        let four_player = self.config.four_player.enabled;
+        let mut goals = Vec::new();
+        let mut scored = Vec::new();
+
+        for ball in self.balls.iter_mut() {
+            let conceder = self.sides.iter()
+                .cloned()
+                .find(|side| (ball.position[side.across()] - goal_line(*side, extent, ball.radius)) * side.facing() <= 0.0);
+            let conceder = match conceder {
+                Some(conceder) => conceder,
+                None => continue
+            };
+
+            // a shield turns the goal line into a wall
+            if self.effect_summary.shield[conceder.index()] {
+                let across = conceder.across();
+                ball.velocity[across] = conceder.facing() * ball.velocity[across].abs();
+                ball.position[across] = goal_line(conceder, extent, ball.radius);
+                self.events.push(PongEvent::WallHit);
+                continue;
+            }
+
+            // with two players any goal is the other one's point, with four
+            // it's the point of whoever touched the ball last, if not the conceder
+            let scorer = if four_player {
+                ball.last_hit.filter(|side| *side != conceder)
+            } else {
+                Some(conceder.opponent())
+            };
+            goals.push((scorer, conceder));
+            scored.push(ball.id);
+        }
+
+        if goals.is_empty() {
+            return;
+        }
+
+        self.longest_rally = self.longest_rally.max(self.rally);
+        self.rally = 0;
+        self.milestone_rally = 0;
+        self.balls.retain(|ball| !scored.contains(&ball.id));
+        self.round_goals += goals.len() as u32;
+
+        for (scorer, conceder) in goals {
+            self.events.push(PongEvent::Goal { scorer, conceder });
+            self.last_conceder = Some(conceder);
+            if four_player {
+                self.concede(scorer, conceder);
+            } else if let Some(scorer) = scorer {
+                self.award_point(scorer);
+            }
+        }
+
+        // one ball means one goal per round, multiball plays on until its round is done
+        let goals_per_round = self.config.multiball.goals_per_round;
+        let round_over = !multiball || self.balls.is_empty() || (goals_per_round > 0 && self.round_goals >= goals_per_round);
+        if round_over && self.winner.is_none() && self.serve.is_none() {
+            self.end_round();
+        }
+    }
+
+    // the next serve goes to whoever conceded last, or anyone still in if they're out
+    fn end_round(&mut self) {
+        let toward = match self.last_conceder {
+            Some(side) if self.sides.contains(&side) => side,
+            _ => self.sides[self.rng.below(self.sides.len() as u32) as usize]
+        };
+        self.begin_serve(toward);
+        self.round_goals = 0;
+        self.multiball_timer = 0.0;
+        self.milestone_rally = 0;
+    }
+
+    // one more point for this side would take the set, never in an endless match
+    fn next_point_wins_set(&self, side : Side) -> bool {
+        let rules = &self.config.rules;
+        let points = self.scores[side.index()] + 1;
+        let other = self.scores.iter().enumerate()
+            .filter(|(index, _)| *index != side.index())
+            .map(|(_, points)| *points)
+            .max()
+            .unwrap_or(0);
+        let lead_needed = if rules.win_by_two { 2 } else { 1 };
+
+        !rules.endless && points >= rules.points_to_win && points >= other + lead_needed
+    }
+
+    fn award_point(&mut self, scorer : Side) {
+        if self.winner.is_some() {
+            return;
+        }
+
+        let wins_set = self.next_point_wins_set(scorer);
+        self.scores[scorer.index()] += 1;
+        if !wins_set {
+            return;
+        }
+
+        self.sets[scorer.index()] += 1;
+
+        if self.sets[scorer.index()] >= self.config.rules.sets_to_win() {
+            self.winner = Some(scorer);
+            self.balls.clear();
+            self.events.push(PongEvent::MatchOver { winner : scorer });
+        } else {
+            self.scores = [0; 4];
+            self.end_round();
+        }
+    }
+
+    // four players: every goal costs the conceder a life, a side out of lives
+    // loses its paddle and its goal turns into a wall, the last side left wins
+    fn concede(&mut self, scorer : Option<Side>, conceder : Side) {
+        if self.winner.is_some() || !self.sides.contains(&conceder) {
+            return;
+        }
+
+        if let Some(scorer) = scorer {
+            self.scores[scorer.index()] += 1;
+        }
+        self.conceded[conceder.index()] += 1;
+        if self.config.rules.endless || self.lives(conceder) > 0 {
+            return;
+        }
+
+        self.sides.retain(|side| *side != conceder);
+        self.paddles.retain(|paddle| paddle.side != conceder);
+        self.effects.retain(|effect| effect.side != conceder);
+        for ball in self.balls.iter_mut() {
+            if ball.stuck.as_ref().is_some_and(|stuck| stuck.side == conceder) {
+                ball.stuck = None;
+            }
+        }
+        self.events.push(PongEvent::Eliminated { side : conceder });
+
+        if let [winner] = self.sides[..] {
+            self.winner = Some(winner);
+            self.balls.clear();
+            self.events.push(PongEvent::MatchOver { winner });
+        }
+    }
+}
+
+// Left and Right, or all four when four_player is on
+fn match_sides(config : &PongConfig) -> &'static [Side] {
+    if config.four_player.enabled { &SIDES } else { &SIDES[..2] }
+}
+
+fn arena_extent(config : &PongConfig) -> [f32; 2] {
+    [config.arena.width, config.arena.height]
+}
+
+// where on its across axis something `inset` from this side's edge of the arena is
+fn goal_line(side : Side, extent : [f32; 2], inset : f32) -> f32 {
+    if side.facing() > 0.0 { inset } else { extent[side.across()] - inset }
+}
+
+fn new_paddle(side : Side, config : &PongConfig) -> PaddleState {
+    let extent = arena_extent(config);
+    let mut position = [extent[0] * 0.5, extent[1] * 0.5];
+    position[side.across()] = goal_line(side, extent, config.paddles.width * 0.5);
+    let mut size = [0.0, 0.0];
+    size[side.across()] = config.paddles.width;
+    size[side.along()] = config.paddles.height;
+
+    PaddleState {
+        side,
+        position,
+        previous_position : position,
+        velocity : 0.0,
+        size
+    }
+}
+
+// keeps a paddle on the arena's edge; with four sides the corners belong to
+// nobody, so paddles stop short of them by a paddle's thickness
+fn keep_in_lane(paddle : &mut PaddleState, config : &PongConfig) {
+    let along = paddle.side.along();
+    let extent = arena_extent(config)[along];
+    let corner = if config.four_player.enabled { config.paddles.width } else { 0.0 };
+    let half = paddle.length() * 0.5;
+    let low = corner + half;
+    let high = (extent - corner - half).max(low); // a paddle grown past its lane stays at the low end
+
+    paddle.position[along] = paddle.position[along].clamp(low, high);
+}
+
+// what a ball ran into during its motion
+enum Contact<'a> {
+    Wall,
+    Paddle(&'a PaddleState),
+    Obstacle(&'a ObstacleState)
+}
+
+fn obstacle_event(obstacle : &ObstacleState) -> PongEvent {
+    match obstacle.shape {
+        Shape::Block { .. } => PongEvent::WallHit,
+        Shape::Bumper { .. } => PongEvent::BumperHit
+    }
+}
+
+fn moving_to_goal(ball : &BallState, paddle : &PaddleState) -> bool {
+    ball.velocity[paddle.side.across()] * paddle.side.facing() < 0.0
+}
+
+fn hit_paddle(ball : &mut BallState, paddle : &PaddleState, config : &PongConfig, effects : &EffectSummary) {
+    let max_angle = config.paddles.max_bounce_angle.to_radians();
+    let speed = (length(ball.velocity) * config.ball.speed_up).min(config.ball.max_speed);
+
+    ball.velocity = with_speed(deflect(ball, paddle, max_angle, config.paddles.english), speed);
+    ball.last_hit = Some(paddle.side);
+
+    let sticky_hold = effects.sticky_hold[paddle.side.index()];
+    if sticky_hold > 0.0 {
+        ball.stuck = Some(Stuck {
+            side : paddle.side,
+            offset : ball.position[paddle.side.along()] - paddle.position[paddle.side.along()],
+            remaining : sticky_hold
+        });
+    }
+}
+
+// where along the paddle the ball touches it, -1.0 at its low end to 1.0 at its high end
+fn hit_offset(ball : &BallState, paddle : &PaddleState) -> f32 {
+    let along = paddle.side.along();
+    ((ball.position[along] - paddle.position[along]) / (paddle.length() * 0.5 + ball.radius)).clamp(-1.0, 1.0)
+}
+
+// outgoing velocity off a paddle: the further from the paddle's center the
+// ball hits, the steeper it leaves, the speed stays the same
+fn deflect(ball : &BallState, paddle : &PaddleState, max_angle : f32, english : f32) -> [f32; 2] {
+    let speed = length(ball.velocity);
+    let side = paddle.side;
+    let along = side.along();
+
+    let mut angle = hit_offset(ball, paddle) * max_angle;
+
+    if english > 0.0 {
+        let velocity_along = speed * angle.sin() + english * paddle.velocity;
+        angle = velocity_along.atan2(speed * angle.cos())
+            .max(-max_angle)
+            .min(max_angle);
+    }
+
+    let mut velocity = [0.0, 0.0];
+    velocity[side.across()] = side.facing() * speed * angle.cos();
+    velocity[along] = speed * angle.sin();
+    velocity
+}
+
+pub(crate) fn length(vector : [f32; 2]) -> f32 {
+    (vector[0] * vector[0] + vector[1] * vector[1]).sqrt()
+}
+
+fn with_speed(velocity : [f32; 2], speed : f32) -> [f32; 2] {
+    let scale = speed / length(velocity);
+    [velocity[0] * scale, velocity[1] * scale]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT : f32 = 1.0 / 120.0;
+
+    // paddles chasing the first ball, in a pattern that changes every few steps
+    fn chasing_inputs(sim : &PongSim, step : u32) -> Inputs {
+        let mut inputs = Inputs::default();
+        for paddle in sim.paddles() {
+            let along = paddle.side.along();
+            let target = sim.balls().first().map_or(50.0, |ball| ball.position[along]);
+            let scale = if (step / 50).is_multiple_of(3) { 0.4 } else { 1.0 };
+            inputs.set(paddle.side, ((target - paddle.position[along]) / 3.0).clamp(-1.0, 1.0) * scale);
+        }
+        inputs
+    }
+
+    // steps until the ball is served and moving
+    fn serve_now(sim : &mut PongSim) {
+        while sim.serve_countdown().is_some() {
+            sim.step(&Inputs::default(), DT);
+        }
+    }
+
+    #[test]
+    fn same_seed_and_inputs_play_the_same() {
+        let mut config = PongConfig::default();
+        config.multiball.enabled = true;
+        config.power_ups.enabled = true;
+        let mut first = PongSim::with_seed(config.clone(), 7);
+        let mut second = PongSim::with_seed(config, 7);
+
+        for step in 0..5000 {
+            let inputs = chasing_inputs(&first, step);
+            first.step(&inputs, DT);
+            second.step(&inputs, DT);
+        }
+
+        assert_eq!(first.checksum(), second.checksum());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn different_seeds_serve_differently() {
+        let mut first = PongSim::with_seed(PongConfig::default(), 1);
+        let mut second = PongSim::with_seed(PongConfig::default(), 2);
+        serve_now(&mut first);
+        serve_now(&mut second);
+
+        assert_ne!(first.balls()[0].velocity, second.balls()[0].velocity);
+    }
+
+    #[test]
+    fn goal_scores_for_the_other_side() {
+        let mut sim = PongSim::with_seed(PongConfig::default(), 3);
+        serve_now(&mut sim);
+        sim.take_events();
+
+        // past the left paddle, heading into the left goal
+        let radius = sim.balls[0].radius;
+        sim.balls[0].position = [radius + 0.2, 90.0];
+        sim.balls[0].velocity = [-100.0, 0.0];
+        sim.step(&Inputs::default(), DT);
+
+        assert_eq!(sim.score(Side::Right), 1);
+        assert_eq!(sim.score(Side::Left), 0);
+        assert!(sim.take_events().contains(&PongEvent::Goal { scorer : Some(Side::Right), conceder : Side::Left }));
+    }
+
+    #[test]
+    fn goal_resets_to_a_serve_toward_the_conceder() {
+        let mut sim = PongSim::with_seed(PongConfig::default(), 4);
+        serve_now(&mut sim);
+        let scored_id = sim.balls[0].id;
+
+        let extent = arena_extent(&sim.config);
+        let radius = sim.balls[0].radius;
+        sim.balls[0].position = [extent[0] - radius - 0.2, 10.0];
+        sim.balls[0].velocity = [100.0, 0.0];
+        sim.step(&Inputs::default(), DT);
+
+        assert_eq!(sim.score(Side::Left), 1);
+        assert_eq!(sim.rally(), 0);
+        assert_eq!(sim.serve_countdown(), Some(sim.config.serve.delay));
+        assert_eq!(sim.balls().len(), 1);
+        let ball = &sim.balls()[0];
+        assert_ne!(ball.id, scored_id);
+        assert_eq!(ball.position, [extent[0] / 2.0, extent[1] / 2.0]);
+        assert_eq!(ball.velocity, [0.0, 0.0]);
+
+        sim.take_events();
+        serve_now(&mut sim);
+        assert!(sim.take_events().contains(&PongEvent::Serve));
+        assert!(sim.balls()[0].velocity[0] > 0.0, "served toward the right, who conceded");
+    }
+
+    #[test]
+    fn reset_starts_a_fresh_match() {
+        let mut sim = PongSim::with_seed(PongConfig::default(), 5);
+        for step in 0..3000 {
+            let inputs = chasing_inputs(&sim, step);
+            sim.step(&inputs, DT);
+        }
+        let last_id = sim.balls().iter().map(|ball| ball.id).max().unwrap_or(0);
+
+        sim.reset();
+        assert_eq!(sim.score(Side::Left) + sim.score(Side::Right), 0);
+        assert_eq!(sim.elapsed(), 0.0);
+        assert!(sim.serve_countdown().is_some());
+        assert!(sim.balls()[0].id > last_id);
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel_through_a_paddle() {
+        let mut sim = PongSim::with_seed(PongConfig::default(), 6);
+        serve_now(&mut sim);
+        sim.take_events();
+
+        // 50 units a step against a paddle 4 wide
+        let paddle_y = sim.paddle(Side::Left).unwrap().position[1];
+        sim.balls[0].position = [90.0, paddle_y];
+        sim.balls[0].velocity = [-6000.0, 0.0];
+        for _ in 0..4 {
+            sim.step(&Inputs::default(), DT);
+        }
+
+        assert_eq!(sim.score(Side::Right), 0);
+        assert!(sim.balls()[0].velocity[0] > 0.0);
+        assert!(sim.take_events().iter().any(|event| match event {
+            PongEvent::PaddleHit { side, .. } => *side == Side::Left,
+            _ => false
+        }));
+    }
+
+    #[test]
+    fn paddle_end_is_a_graze_not_a_hit() {
+        let mut sim = PongSim::with_seed(PongConfig::default(), 8);
+        serve_now(&mut sim);
+        sim.take_events();
+
+        // just below the left paddle, moving up into its end
+        let (min, _) = sim.paddle(Side::Left).unwrap().bounds();
+        let radius = sim.balls[0].radius;
+        sim.balls[0].position = [radius + 0.5, min[1] - radius - 0.5];
+        sim.balls[0].velocity = [0.0, 100.0];
+        sim.step(&Inputs::default(), DT);
+
+        assert_eq!(sim.take_events(), vec![PongEvent::PaddleGraze { side : Side::Left }]);
+        assert!(sim.balls()[0].velocity[1] < 0.0);
+        assert_eq!(sim.rally(), 0);
+        assert_eq!(sim.balls()[0].last_hit, None);
+    }
+}
@@ -0,0 +1,164 @@
+// The level's obstacles: blocks the ball bounces off like off a wall and
+// bumpers that send it away faster, either of them standing still or
+// swinging along a path.
+
+use std::f32::consts::PI;
+
+use crate::level::{PathConfig, Shape};
+use super::collision::{point_in_rect, reflect, sweep_circle_point, sweep_circle_rect, Hit};
+use super::{length, with_speed, BallState, PongSim};
+
+// degrees, a ball sent off an obstacle straight along an axis could bounce
+// between it and a wall forever without reaching a paddle
+const MIN_AXIS_ANGLE : f32 = 15.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObstacleState {
+    pub id : u32,
+    pub shape : Shape,
+    pub position : [f32; 2],
+    pub previous_position : [f32; 2], // before the last step, for drawing between steps
+    pub velocity : [f32; 2],
+    origin : [f32; 2],
+    path : Option<PathConfig>
+}
+
+impl ObstacleState {
+    pub fn interpolated_position(&self, alpha : f32) -> [f32; 2] {
+        [
+            self.previous_position[0] + (self.position[0] - self.previous_position[0]) * alpha,
+            self.previous_position[1] + (self.position[1] - self.previous_position[1]) * alpha
+        ]
+    }
+
+    pub fn bounds(&self) -> ([f32; 2], [f32; 2]) {
+        let half = self.shape.half_extent();
+        (
+            [self.position[0] - half[0], self.position[1] - half[1]],
+            [self.position[0] + half[0], self.position[1] + half[1]]
+        )
+    }
+
+    // where `motion` first brings the ball against this obstacle
+    pub(super) fn sweep(&self, ball : &BallState, motion : [f32; 2]) -> Option<Hit> {
+        match self.shape {
+            Shape::Block { .. } => {
+                let (min, max) = self.bounds();
+                sweep_circle_rect(ball.position, motion, ball.radius, min, max)
+            },
+            Shape::Bumper { radius, .. } => sweep_circle_point(ball.position, motion, ball.radius + radius, self.position)
+        }
+    }
+
+    // sends the ball off the obstacle it touched, faster off a bumper
+    pub(super) fn bounce(&self, ball : &mut BallState, normal : [f32; 2], max_speed : f32) {
+        ball.velocity = off_axis(reflect(ball.velocity, normal), normal);
+        if let Shape::Bumper { boost, .. } = self.shape {
+            let speed = (length(ball.velocity) * boost).min(max_speed.max(length(ball.velocity)));
+            ball.velocity = with_speed(ball.velocity, speed);
+        }
+    }
+
+    // a moving obstacle can run into a ball; pushes the ball out to the
+    // nearest edge and the normal there, None when they don't overlap
+    pub(super) fn push_out(&self, ball : &mut BallState) -> Option<[f32; 2]> {
+        match self.shape {
+            Shape::Block { .. } => {
+                let (min, max) = self.bounds();
+                let grown = ([min[0] - ball.radius, min[1] - ball.radius], [max[0] + ball.radius, max[1] + ball.radius]);
+                if !point_in_rect(ball.position[0], ball.position[1], grown.0[0], grown.0[1], grown.1[0], grown.1[1]) {
+                    return None;
+                }
+
+                // out through whichever edge is closest
+                let exits = [
+                    (ball.position[0] - grown.0[0], 0, -1.0),
+                    (grown.1[0] - ball.position[0], 0, 1.0),
+                    (ball.position[1] - grown.0[1], 1, -1.0),
+                    (grown.1[1] - ball.position[1], 1, 1.0)
+                ];
+                let (depth, axis, direction) = exits.iter()
+                    .cloned()
+                    .fold(exits[0], |closest, exit| if exit.0 < closest.0 { exit } else { closest });
+                ball.position[axis] += depth * direction;
+
+                let mut normal = [0.0, 0.0];
+                normal[axis] = direction;
+                Some(normal)
+            },
+            Shape::Bumper { radius, .. } => {
+                let delta = [ball.position[0] - self.position[0], ball.position[1] - self.position[1]];
+                let distance = length(delta);
+                let reach = radius + ball.radius;
+                if distance >= reach {
+                    return None;
+                }
+
+                let normal = if distance > 0.0 { [delta[0] / distance, delta[1] / distance] } else { [0.0, 1.0] };
+                ball.position = [self.position[0] + normal[0] * reach, self.position[1] + normal[1] * reach];
+                Some(normal)
+            }
+        }
+    }
+}
+
+impl PongSim {
+    pub(super) fn spawn_obstacles(&mut self) {
+        let obstacles : Vec<ObstacleState> = self.config.level.obstacles.iter()
+            .enumerate()
+            .map(|(index, obstacle)| ObstacleState {
+                id : self.next_obstacle_id + index as u32,
+                shape : obstacle.shape,
+                position : obstacle.position,
+                previous_position : obstacle.position,
+                velocity : [0.0, 0.0],
+                origin : obstacle.position,
+                path : obstacle.path
+            })
+            .collect();
+
+        self.next_obstacle_id += obstacles.len() as u32;
+        self.obstacles = obstacles;
+        self.move_obstacles(0.0); // into their starting phase
+    }
+
+    // eased swings, the same point of the path at the same match time
+    pub(super) fn move_obstacles(&mut self, dt : f32) {
+        let time = self.elapsed;
+
+        for obstacle in self.obstacles.iter_mut() {
+            let path = match obstacle.path {
+                Some(path) => path,
+                None => continue
+            };
+            let swing = 0.5 - 0.5 * (2.0 * PI * (time / path.period + path.phase)).cos();
+            let position = [
+                obstacle.origin[0] + path.offset[0] * swing,
+                obstacle.origin[1] + path.offset[1] * swing
+            ];
+
+            if dt > 0.0 {
+                obstacle.velocity = [(position[0] - obstacle.position[0]) / dt, (position[1] - obstacle.position[1]) / dt];
+                obstacle.position = position;
+            } else {
+                obstacle.position = position;
+                obstacle.previous_position = position;
+            }
+        }
+    }
+}
+
+fn off_axis(velocity : [f32; 2], normal : [f32; 2]) -> [f32; 2] {
+    let speed = length(velocity);
+    let min = speed * MIN_AXIS_ANGLE.to_radians().sin();
+
+    let mut turned = velocity;
+    for axis in 0..2 {
+        if turned[axis].abs() < min {
+            // away from the obstacle, so the turn can't send the ball back into it
+            let direction = if normal[axis] != 0.0 { normal[axis].signum() } else if turned[axis] != 0.0 { turned[axis].signum() } else { 1.0 };
+            turned[axis] = direction * min;
+        }
+    }
+    with_speed(turned, speed)
+}
@@ -0,0 +1,218 @@
+// Pickups that appear in the arena and the timed effects they give to the
+// paddle that last touched the ball passing through them.
+
+use crate::config::{EffectTarget, PowerUpEntry, PowerUpKind, Stacking};
+use super::{arena_extent, keep_in_lane, length, PongSim, Side};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickupState {
+    pub id : u32,
+    pub kind : PowerUpKind,
+    pub entry : usize, // index in the config's spawn table
+    pub position : [f32; 2],
+    pub radius : f32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveEffect {
+    pub kind : PowerUpKind,
+    pub side : Side,
+    pub remaining : f32,
+    pub magnitude : f32
+}
+
+// what the running effects add up to, worked out once per step
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectSummary {
+    pub height_factor : [f32; 4], // of the paddle's length, whichever way it lies
+    pub ball_speed_factor : f32,
+    pub sticky_hold : [f32; 4], // 0.0 when the paddle isn't sticky
+    pub shield : [bool; 4],
+    pub reversed : [bool; 4]
+}
+
+impl Default for EffectSummary {
+    fn default() -> Self {
+        EffectSummary {
+            height_factor : [1.0; 4],
+            ball_speed_factor : 1.0,
+            sticky_hold : [0.0; 4],
+            shield : [false; 4],
+            reversed : [false; 4]
+        }
+    }
+}
+
+impl PongSim {
+    pub(super) fn update_power_ups(&mut self, dt : f32) {
+        for effect in self.effects.iter_mut() {
+            effect.remaining -= dt;
+        }
+        self.effects.retain(|effect| effect.remaining > 0.0);
+
+        // pickups only appear while a ball is in play
+        let config = &self.config.power_ups;
+        if config.enabled && !self.balls.is_empty() {
+            self.pickup_timer += dt;
+            if self.pickup_timer >= config.spawn_interval {
+                self.pickup_timer = 0.0;
+                if (self.pickups.len() as u32) < config.max_pickups {
+                    self.spawn_pickup();
+                }
+            }
+        }
+
+        self.summarize_effects();
+    }
+
+    fn spawn_pickup(&mut self) {
+        let config = &self.config.power_ups;
+        let arena = &self.config.arena;
+
+        let total : u32 = config.table.iter().map(|entry| entry.weight).sum();
+        if total == 0 {
+            return; // nothing to pick from, the config check keeps this from happening
+        }
+        let mut roll = self.rng.below(total);
+        let entry = config.table.iter()
+            .position(|entry| {
+                if roll < entry.weight {
+                    true
+                } else {
+                    roll -= entry.weight;
+                    false
+                }
+            })
+            .expect("roll is below the total weight");
+
+        // the middle half of the arena, clear of the paddles
+        let radius = config.radius;
+        let x = self.rng.range(arena.width * 0.25, arena.width * 0.75);
+        let y = if self.config.four_player.enabled {
+            self.rng.range(arena.height * 0.25, arena.height * 0.75)
+        } else {
+            self.rng.range(radius, arena.height - radius)
+        };
+
+        self.pickups.push(PickupState {
+            id : self.next_pickup_id,
+            kind : config.table[entry].kind,
+            entry,
+            position : [x, y],
+            radius
+        });
+        self.next_pickup_id += 1;
+    }
+
+    // a ball nobody has hit yet passes through pickups without taking them
+    pub(super) fn collect_power_ups(&mut self) {
+        let mut collected = Vec::new();
+        for (index, pickup) in self.pickups.iter().enumerate() {
+            let collector = self.balls.iter()
+                .filter(|ball| {
+                    let delta = [ball.position[0] - pickup.position[0], ball.position[1] - pickup.position[1]];
+                    length(delta) < ball.radius + pickup.radius
+                })
+                .find_map(|ball| ball.last_hit);
+
+            if let Some(side) = collector {
+                collected.push((index, side));
+            }
+        }
+
+        if collected.is_empty() {
+            return;
+        }
+
+        for (index, side) in collected.into_iter().rev() {
+            let pickup = self.pickups.remove(index);
+            let entry = self.config.power_ups.table[pickup.entry].clone();
+            self.apply_effect(&entry, side);
+        }
+        self.summarize_effects();
+    }
+
+    fn apply_effect(&mut self, entry : &PowerUpEntry, collector : Side) {
+        let side = match entry.target {
+            EffectTarget::Collector => collector,
+            EffectTarget::Opponent => collector.opponent()
+        };
+        let running : Vec<usize> = self.effects.iter().enumerate()
+            .filter(|(_, effect)| effect.kind == entry.kind && effect.side == side)
+            .map(|(index, _)| index)
+            .collect();
+        let fresh = ActiveEffect {
+            kind : entry.kind,
+            side,
+            remaining : entry.duration,
+            magnitude : entry.magnitude
+        };
+
+        match (entry.stacking, running.first()) {
+            (_, None) => self.effects.push(fresh),
+            (Stacking::Refresh, Some(&index)) => self.effects[index].remaining = entry.duration,
+            (Stacking::Extend, Some(&index)) => self.effects[index].remaining += entry.duration,
+            (Stacking::Stack(max), Some(_)) => {
+                if (running.len() as u32) < max {
+                    self.effects.push(fresh);
+                } else {
+                    // full stack: the one closest to running out starts over
+                    let oldest = running.iter()
+                        .min_by(|a, b| self.effects[**a].remaining.total_cmp(&self.effects[**b].remaining))
+                        .copied()
+                        .unwrap();
+                    self.effects[oldest].remaining = entry.duration;
+                }
+            },
+            (Stacking::Ignore, Some(_)) => {}
+        }
+    }
+
+    fn summarize_effects(&mut self) {
+        let mut summary = EffectSummary::default();
+        for effect in self.effects.iter() {
+            let index = effect.side.index();
+            match effect.kind {
+                PowerUpKind::Grow | PowerUpKind::Shrink => summary.height_factor[index] *= effect.magnitude,
+                PowerUpKind::SlowBall => summary.ball_speed_factor *= effect.magnitude,
+                PowerUpKind::Sticky => summary.sticky_hold[index] = summary.sticky_hold[index].max(effect.magnitude),
+                PowerUpKind::Shield => summary.shield[index] = true,
+                PowerUpKind::ReverseControls => summary.reversed[index] = true
+            }
+        }
+
+        // paddles follow their size effects but never outgrow the arena
+        let base_length = self.config.paddles.height;
+        let extent = arena_extent(&self.config);
+        for paddle in self.paddles.iter_mut() {
+            let along = paddle.side.along();
+            paddle.size[along] = (base_length * summary.height_factor[paddle.side.index()]).min(extent[along]);
+            keep_in_lane(paddle, &self.config);
+        }
+
+        self.effect_summary = summary;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::PongConfig;
+    use crate::sim::{Inputs, PongSim};
+
+    #[test]
+    fn no_pickups_from_a_table_without_weights() {
+        let mut config = PongConfig::default();
+        config.power_ups.enabled = true;
+        config.power_ups.spawn_interval = 0.1;
+        for entry in config.power_ups.table.iter_mut() {
+            entry.weight = 0;
+        }
+        assert!(config.validate().is_err());
+
+        let mut sim = PongSim::with_seed(config, 1);
+        for _ in 0..600 {
+            sim.step(&Inputs::default(), 1.0 / 120.0);
+        }
+        assert!(sim.pickups().is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+// Small splitmix64 generator. The sim keeps its own randomness instead of
+// going through rand so a seed replays the same match on any platform and
+// with any rand version, and a cloned sim rolls exactly what the original does.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimRng {
+    state : u64
+}
+
+impl SimRng {
+    pub fn new(seed : u64) -> SimRng {
+        SimRng { state : seed }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0.0 - 1.0, 1.0 excluded
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn range(&mut self, low : f32, high : f32) -> f32 {
+        low + (high - low) * self.next_f32()
+    }
+
+    pub fn below(&mut self, bound : u32) -> u32 {
+        (self.next_u64() % bound as u64) as u32
+    }
+}
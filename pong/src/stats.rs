@@ -0,0 +1,220 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::sim::{PongEvent, PongSim, Side, SIDES};
+
+// paddle hits are counted in this many equal zones, from the paddle's low end to its high end
+pub const HIT_ZONES : usize = 5;
+
+// numbers for charting a match, AI tuning especially: StatsSystem feeds it
+// the sim's hits and goals, WinnerSystem the ball speed and who has the ball
+// every frame. Shown on the summary screen after the match and written out
+// as a JSON file per match and a row of totals in a CSV shared by all of them
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MatchStats {
+    pub timestamp : u64,       // unix seconds at the end of the match
+    pub mode : String,
+    pub winner : Option<Side>,
+    pub duration : f32,        // seconds of play
+    pub rallies : Vec<u32>,    // paddle hits in every rally that ended in a goal
+    pub average_speed : f32,   // of the fastest ball in play, over the time one was
+    pub max_speed : f32,
+    pub sides : Vec<SideStats>,
+    #[serde(skip)]
+    rally : u32,
+    #[serde(skip)]
+    speed_time : f32,          // seconds a ball was in play
+    #[serde(skip)]
+    speed_distance : f32
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SideStats {
+    pub side : Side,
+    pub player : String,
+    pub hits : u32,
+    pub hit_zones : [u32; HIT_ZONES],
+    pub possession : f32 // seconds the ball was last touched by this side
+}
+
+impl MatchStats {
+    pub fn new(mode : String, sides : &[Side], players : Vec<String>) -> MatchStats {
+        MatchStats {
+            mode,
+            sides : sides.iter()
+                .zip(players)
+                .map(|(side, player)| SideStats { side : *side, player, hits : 0, hit_zones : [0; HIT_ZONES], possession : 0.0 })
+                .collect(),
+            ..MatchStats::default()
+        }
+    }
+
+    // an online rollback can hand out a hit twice, the odd extra count is let be
+    pub fn record(&mut self, event : &PongEvent) {
+        match *event {
+            PongEvent::PaddleHit { side, offset, .. } => {
+                self.rally += 1;
+                if let Some(stats) = self.side_mut(side) {
+                    let zone = ((offset + 1.0) * 0.5 * HIT_ZONES as f32) as usize;
+                    stats.hits += 1;
+                    stats.hit_zones[zone.min(HIT_ZONES - 1)] += 1;
+                }
+            },
+            PongEvent::Goal { .. } => {
+                self.rallies.push(self.rally);
+                self.rally = 0;
+            },
+            _ => {}
+        }
+    }
+
+    // catches up with the sim's clock; the time since the last sample goes
+    // to the ball speed and, shared between the balls in play, to the side
+    // that touched each one last
+    pub fn sample(&mut self, sim : &PongSim) {
+        let dt = sim.elapsed() - self.duration;
+        if dt <= 0.0 {
+            return; // nothing stepped, or rolled back
+        }
+        self.duration = sim.elapsed();
+
+        let speed = sim.ball_speed();
+        if speed > 0.0 {
+            self.speed_time += dt;
+            self.speed_distance += speed * dt;
+            self.average_speed = self.speed_distance / self.speed_time;
+            self.max_speed = self.max_speed.max(speed);
+        }
+
+        let share = dt / sim.balls().len().max(1) as f32;
+        for ball in sim.balls() {
+            if let Some(stats) = ball.last_hit.and_then(|side| self.side_mut(side)) {
+                stats.possession += share;
+            }
+        }
+    }
+
+    pub fn finish(&mut self, sim : &PongSim, timestamp : u64) {
+        self.sample(sim);
+        self.winner = sim.winner();
+        self.timestamp = timestamp;
+    }
+
+    pub fn longest_rally(&self) -> u32 {
+        self.rallies.iter().cloned().max().unwrap_or(0)
+    }
+
+    pub fn average_rally(&self) -> f32 {
+        if self.rallies.is_empty() {
+            0.0
+        } else {
+            self.rallies.iter().sum::<u32>() as f32 / self.rallies.len() as f32
+        }
+    }
+
+    pub fn side(&self, side : Side) -> Option<&SideStats> {
+        self.sides.iter().find(|stats| stats.side == side)
+    }
+
+    fn side_mut(&mut self, side : Side) -> Option<&mut SideStats> {
+        self.sides.iter_mut().find(|stats| stats.side == side)
+    }
+
+    pub fn save_json<P : AsRef<Path>>(&self, path : P) -> Result<(), StatsError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| StatsError::Json(e.to_string()))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    // one row per match, the header goes in with the first; every side has
+    // its columns, left empty when it didn't play
+    pub fn append_csv<P : AsRef<Path>>(&self, path : P) -> Result<(), StatsError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        if new {
+            let mut header = vec!["timestamp", "mode", "winner", "duration", "rallies", "longest_rally", "average_rally", "average_speed", "max_speed"]
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<String>>();
+            for side in SIDES.iter() {
+                let name = format!("{:?}", side).to_lowercase();
+                header.push(format!("{}_player", name));
+                header.push(format!("{}_hits", name));
+                header.push(format!("{}_possession", name));
+            }
+            writeln!(file, "{}", header.join(","))?;
+        }
+
+        let mut row = vec![
+            self.timestamp.to_string(),
+            self.mode.clone(),
+            self.winner.map_or(String::new(), |winner| format!("{:?}", winner)),
+            format!("{:.2}", self.duration),
+            self.rallies.len().to_string(),
+            self.longest_rally().to_string(),
+            format!("{:.2}", self.average_rally()),
+            format!("{:.2}", self.average_speed),
+            format!("{:.2}", self.max_speed)
+        ];
+        for side in SIDES.iter() {
+            match self.side(*side) {
+                Some(stats) => {
+                    row.push(csv_text(&stats.player));
+                    row.push(stats.hits.to_string());
+                    row.push(format!("{:.2}", stats.possession));
+                },
+                None => row.extend(vec![String::new(), String::new(), String::new()])
+            }
+        }
+        writeln!(file, "{}", row.join(","))?;
+        Ok(())
+    }
+}
+
+// player names are free text, quoted when they'd break the row
+pub fn csv_text(text : &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[derive(Debug)]
+pub enum StatsError {
+    Io(std::io::Error),
+    Json(String)
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Io(e) => write!(f, "could not write match stats: {}", e),
+            StatsError::Json(e) => write!(f, "could not encode match stats: {}", e)
+        }
+    }
+}
+
+impl Error for StatsError {}
+
+impl From<std::io::Error> for StatsError {
+    fn from(e : std::io::Error) -> Self {
+        StatsError::Io(e)
+    }
+}
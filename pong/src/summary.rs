@@ -0,0 +1,85 @@
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode},
+    prelude::*,
+    ui::{Anchor, FontHandle, UiButton, UiEvent, UiEventType, UiText, UiTransform}
+};
+
+use crate::menu::{create_backdrop, create_button, delete_button, is_clicked, load_font};
+use crate::stats::MatchStats;
+
+const ROW_HEIGHT : f32 = 26.0;
+
+// the finished match's MatchStats, pushed over GameOver
+#[derive(Default)]
+pub struct MatchSummary {
+    backdrop : Option<Entity>,
+    texts : Vec<Entity>,
+    back : Option<UiButton>
+}
+
+impl MatchSummary {
+    fn create_text(&mut self, world : &mut World, font : FontHandle, text : String, y : f32, size : f32) {
+        let transform = UiTransform::new(
+            "summary".to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
+            0.0, y, 11.0, 480.0, size + 8.0
+        );
+        self.texts.push(world
+            .create_entity()
+            .with(transform)
+            .with(UiText::new(font, text, [1.0, 1.0, 1.0, 1.0], size))
+            .build());
+    }
+}
+
+impl SimpleState for MatchSummary {
+    fn on_start(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        let world = data.world;
+        let font = load_font(world);
+        let stats = (*world.read_resource::<MatchStats>()).clone();
+
+        let mut rows = vec![
+            format!("{:.0} s, {} rallies", stats.duration, stats.rallies.len()),
+            format!("Rally: {:.1} hits on average, {} longest", stats.average_rally(), stats.longest_rally()),
+            format!("Ball speed: {:.0} on average, {:.0} top", stats.average_speed, stats.max_speed),
+            String::new()
+        ];
+        for side in stats.sides.iter() {
+            let possession = if stats.duration > 0.0 { side.possession / stats.duration * 100.0 } else { 0.0 };
+            let zones : Vec<String> = side.hit_zones.iter().map(|hits| hits.to_string()).collect();
+            rows.push(format!("{}: {} hits, {:.0}% of the time", side.player, side.hits, possession));
+            rows.push(format!("along the paddle {}", zones.join(" ")));
+        }
+
+        self.backdrop = Some(create_backdrop(world));
+        self.create_text(world, font.clone(), "MATCH STATS".to_string(), -30.0, 32.0);
+        for (index, row) in rows.into_iter().enumerate() {
+            self.create_text(world, font.clone(), row, -80.0 - index as f32 * ROW_HEIGHT, 16.0);
+        }
+        self.back = Some(create_button(world, font, "Back", -210.0));
+    }
+
+    fn on_stop(&mut self, data : StateData<'_, GameData<'_, '_ >>) {
+        if let Some(backdrop) = self.backdrop.take() {
+            data.world.delete_entity(backdrop).ok();
+        }
+        for text in self.texts.drain(..) {
+            data.world.delete_entity(text).ok();
+        }
+        if let Some(back) = self.back.take() {
+            delete_button(data.world, &back);
+        }
+    }
+
+    fn handle_event(&mut self, _data : StateData<'_, GameData<'_, '_ >>, event : StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Window(event) if is_key_down(event, VirtualKeyCode::Escape) => Trans::Pop,
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "stats" => Trans::Pop,
+            StateEvent::Ui(UiEvent { event_type : UiEventType::Click, target }) if is_clicked(&self.back, *target) => {
+                Trans::Pop
+            },
+            _ => Trans::None
+        }
+    }
+}
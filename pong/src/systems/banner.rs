@@ -0,0 +1,137 @@
+use amethyst::{
+    core::timing::Time,
+    ecs::prelude::{Read, ReadExpect, System, SystemData, World, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+    ui::{UiText, UiTransform}
+};
+
+use crate::play::Banners;
+use crate::replay::ReplayPlayer;
+use crate::sim::{PongEvent, PongSim, Side};
+
+const HEADLINE_SECONDS : f32 = 1.5;
+const HEADLINE_SIZE : f32 = 32.0;
+const COUNTDOWN_SIZE : f32 = 80.0;
+const MATCH_POINT_SIZE : f32 = 20.0;
+
+// animates the banners: a headline that pops in and fades after goals, the
+// countdown before a serve, a pulsing match point marker under the scores
+// and the playback speed while watching a replay
+#[derive(Default)]
+pub struct BannerSystem {
+    reader : Option<ReaderId<PongEvent>>,
+    headline_age : f32
+}
+
+impl<'s> System<'s> for BannerSystem {
+    type SystemData = (
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiTransform>,
+        ReadExpect<'s, Banners>,
+        ReadExpect<'s, PongSim>,
+        Read<'s, EventChannel<PongEvent>>,
+        Read<'s, Time>,
+        Option<Read<'s, ReplayPlayer>>
+    );
+
+    fn setup(&mut self, world : &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(world.fetch_mut::<EventChannel<PongEvent>>().register_reader());
+        self.headline_age = HEADLINE_SECONDS;
+    }
+
+    fn run(&mut self, (mut ui_text, mut transforms, banners, sim, events, time, player) : Self::SystemData) {
+        let name = |side : Side| sim.config().players.name(side).to_string();
+        let mut headline = None;
+        for event in events.read(self.reader.as_mut().expect("BannerSystem was set up")) {
+            match event {
+                PongEvent::Goal { scorer : Some(scorer), .. } => {
+                    // the sim resets the points once a set is won
+                    let set_won = !sim.config().four_player.enabled
+                        && sim.score(Side::Left) == 0 && sim.score(Side::Right) == 0;
+                    headline = Some(if set_won {
+                        format!("{} takes the set", name(*scorer))
+                    } else {
+                        format!("{} scores!", name(*scorer))
+                    });
+                },
+                PongEvent::Goal { scorer : None, conceder } => {
+                    headline = Some(format!("{} concedes", name(*conceder)));
+                },
+                PongEvent::Eliminated { side } => {
+                    headline = Some(format!("{} is out", name(*side)));
+                },
+                PongEvent::MatchOver { winner } => {
+                    headline = Some(format!("{} wins!", name(*winner)));
+                },
+                _ => {}
+            }
+        }
+
+        if let Some(text) = ui_text.get_mut(banners.headline) {
+            if let Some(headline) = headline {
+                text.text = headline;
+                self.headline_age = 0.0;
+            } else {
+                self.headline_age += time.delta_seconds();
+            }
+
+            // pops in oversized, settles, then fades out over the last half
+            let progress = (self.headline_age / HEADLINE_SECONDS).min(1.0);
+            text.font_size = HEADLINE_SIZE * (1.0 + 0.5 * (1.0 - progress * 4.0).max(0.0));
+            text.color[3] = (2.0 - progress * 2.0).min(1.0);
+        }
+
+        if let Some(text) = ui_text.get_mut(banners.countdown) {
+            match sim.serve_countdown() {
+                Some(remaining) if remaining > 0.0 => {
+                    // every number starts big and shrinks and fades through its second
+                    let within = remaining - remaining.floor();
+                    let within = if within == 0.0 { 1.0 } else { within };
+                    text.text = remaining.ceil().to_string();
+                    text.font_size = COUNTDOWN_SIZE * (0.7 + 0.5 * within);
+                    text.color[3] = within;
+                },
+                _ => text.color[3] = 0.0
+            }
+        }
+
+        let at_match_point : Vec<Side> = [Side::Left, Side::Right].iter()
+            .cloned()
+            .filter(|side| sim.match_point(*side))
+            .collect();
+        if let Some(text) = ui_text.get_mut(banners.match_point) {
+            if at_match_point.is_empty() {
+                text.color[3] = 0.0;
+            } else {
+                let pulse = (time.absolute_time_seconds() as f32 * 6.0).sin();
+                text.text = "MATCH POINT".to_string();
+                text.font_size = MATCH_POINT_SIZE * (1.0 + 0.1 * pulse);
+                text.color[3] = 0.75 + 0.25 * pulse;
+            }
+        }
+
+        // under the score of whoever has it, centered when both do
+        if let Some(transform) = transforms.get_mut(banners.match_point) {
+            transform.local_x = match at_match_point.as_slice() {
+                [Side::Left] => 50.0,
+                [Side::Right] => -50.0,
+                _ => 0.0
+            };
+        }
+
+        if let Some(text) = ui_text.get_mut(banners.replay) {
+            match player {
+                Some(player) if player.paused => {
+                    text.text = "REPLAY PAUSED".to_string();
+                    text.color[3] = 1.0;
+                },
+                Some(player) => {
+                    text.text = format!("REPLAY {}x", player.speed());
+                    text.color[3] = 1.0;
+                },
+                None => text.color[3] = 0.0
+            }
+        }
+    }
+}
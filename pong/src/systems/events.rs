@@ -0,0 +1,21 @@
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::{System, SystemData, Write, WriteExpect};
+use amethyst::shrev::EventChannel;
+
+use crate::sim::{PongEvent, PongSim};
+
+// publishes what happened in the sim's steps this frame, anything that wants
+// to react to hits, goals or the end of a match reads the EventChannel
+#[derive(SystemDesc)]
+pub struct PongEventSystem;
+
+impl<'s> System<'s> for PongEventSystem {
+    type SystemData = (
+        WriteExpect<'s, PongSim>,
+        Write<'s, EventChannel<PongEvent>>
+    );
+
+    fn run(&mut self, (mut sim, mut channel) : Self::SystemData) {
+        channel.iter_write(sim.take_events());
+    }
+}
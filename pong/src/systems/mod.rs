@@ -1,10 +1,24 @@
 
+pub use self::banner::BannerSystem;
+pub use self::events::PongEventSystem;
+pub use self::obstacle::ObstacleSystem;
 pub use self::paddle::PaddleSystem;
-pub use self::move_balls::MoveBallSystem;
-pub use self::bounce::BounceSystem;
+pub use self::power_up::PowerUpSystem;
+pub use self::simulation::SimulationSystem;
+pub use self::sound::SoundSystem;
+pub use self::stats::StatsSystem;
+pub use self::sync::SyncSystem;
+pub use self::viewport::ViewportSystem;
 pub use self::winner::WinnerSystem;
 
+mod banner;
+mod events;
+mod obstacle;
 mod paddle;
-mod move_balls;
-mod bounce;
+mod power_up;
+mod simulation;
+mod sound;
+mod stats;
+mod sync;
+mod viewport;
 mod winner;
\ No newline at end of file
@@ -1,27 +0,0 @@
-
-use amethyst::{
-    core::timing::Time,
-    core::transform::Transform,
-    derive::SystemDesc,
-    ecs::prelude::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
-};
-
-use crate::pong::Ball;
-
-#[derive(SystemDesc)]
-pub struct MoveBallSystem;
-
-impl<'s> System<'s> for MoveBallSystem {
-    type SystemData = (
-        ReadStorage<'s, Ball>,
-        WriteStorage<'s, Transform>,
-        Read<'s, Time>
-    );
-
-    fn run(&mut self, (balls, mut transforms, time) : Self::SystemData) {
-        for (ball, transform) in (&balls, &mut transforms).join() {
-            transform.prepend_translation_x(ball.velocity[0] * time.delta_seconds());
-            transform.prepend_translation_y(ball.velocity[1] * time.delta_seconds());
-        }
-    }
-}
\ No newline at end of file
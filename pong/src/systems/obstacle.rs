@@ -0,0 +1,79 @@
+use amethyst::core::math::Vector3;
+use amethyst::core::Transform;
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::{Entities, Join, Read, ReadExpect, System, SystemData, WriteStorage};
+use amethyst::renderer::palette::Srgba;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use crate::level::Shape;
+use crate::play::{Interpolation, Obstacle, PongSprites, BALL_SPRITE_SIZE, PADDLE_SPRITE_SIZE};
+use crate::sim::PongSim;
+
+// shows the level's obstacles, blocks as stretched paddle sprites and
+// bumpers as big balls; a new level between matches brings new ids, so the
+// old entities go and new ones are spawned
+#[derive(SystemDesc)]
+pub struct ObstacleSystem;
+
+impl<'s> System<'s> for ObstacleSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Obstacle>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        ReadExpect<'s, PongSim>,
+        ReadExpect<'s, PongSprites>,
+        Read<'s, Interpolation>
+    );
+
+    fn run(&mut self, (entities, mut obstacles, mut transforms, mut sprites, mut tints, sim, pong_sprites, interpolation) : Self::SystemData) {
+        let alpha = interpolation.alpha;
+
+        let mut shown = Vec::new();
+        for (entity, obstacle, transform) in (&entities, &obstacles, &mut transforms).join() {
+            match sim.obstacles().iter().find(|state| state.id == obstacle.id) {
+                Some(state) => {
+                    let [x, y] = state.interpolated_position(alpha);
+                    transform.set_translation_x(x);
+                    transform.set_translation_y(y);
+                    shown.push(obstacle.id);
+                },
+                None => {
+                    entities.delete(entity).expect("obstacle entity is alive");
+                }
+            }
+        }
+
+        for state in sim.obstacles().iter().filter(|state| !shown.contains(&state.id)) {
+            let (sprite_number, scale, colour) = match state.shape {
+                Shape::Block { width, height } => (
+                    0,
+                    Vector3::new(width / PADDLE_SPRITE_SIZE[0], height / PADDLE_SPRITE_SIZE[1], 1.0),
+                    Srgba::new(0.6, 0.6, 0.7, 1.0)
+                ),
+                Shape::Bumper { radius, .. } => (
+                    1,
+                    Vector3::new(radius * 2.0 / BALL_SPRITE_SIZE, radius * 2.0 / BALL_SPRITE_SIZE, 1.0),
+                    Srgba::new(1.0, 0.5, 0.2, 1.0)
+                )
+            };
+            let [x, y] = state.interpolated_position(alpha);
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(x, y, 0.0);
+            transform.set_scale(scale);
+
+            entities
+                .build_entity()
+                .with(Obstacle { id : state.id }, &mut obstacles)
+                .with(transform, &mut transforms)
+                .with(SpriteRender {
+                    sprite_sheet : pong_sprites.sheet.clone(),
+                    sprite_number
+                }, &mut sprites)
+                .with(Tint(colour), &mut tints)
+                .build();
+        }
+    }
+}
@@ -1,37 +1,41 @@
-
-use amethyst::core::{Transform};
+use amethyst::core::timing::Time;
 use amethyst::derive::SystemDesc;
-use amethyst::ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage};
+use amethyst::ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, Write, WriteStorage};
 use amethyst::input::{InputHandler, StringBindings};
 
-use crate::pong::{Paddle, Side, ARENA_HEIGHT, PADDLE_HEIGHT};
+use crate::controller::{AxisInput, Observation};
+use crate::play::{Controlled, Paddle};
+use crate::sim::{Inputs, PongSim};
 
+// asks each paddle's controller, keyboard, AI or bot alike, how to move and
+// hands the answers to the sim as its inputs
 #[derive(SystemDesc)]
 pub struct PaddleSystem;
 
 impl<'s> System<'s> for PaddleSystem {
     type SystemData = (
-        WriteStorage<'s, Transform>, 
-        ReadStorage<'s, Paddle>, 
-        Read<'s, InputHandler<StringBindings>>
+        ReadStorage<'s, Paddle>,
+        WriteStorage<'s, Controlled>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        ReadExpect<'s, PongSim>,
+        Write<'s, Inputs>
     );
 
-    fn run(&mut self, (mut transforms, paddles, input) : Self::SystemData) {
-        for (paddle, transform) in (&paddles, &mut transforms).join() {
-            let movement = match paddle.side {
-                Side::Left => input.axis_value("left_paddle"),
-                Side::Right => input.axis_value("right_paddle"),
-            };
-            if let Some(mv_amount) = movement {
-                if mv_amount != 0.0 {
-                    let paddle_y = transform.translation().y;
-                    let scaled_movement = 1.2 * mv_amount as f32;
-                    transform.set_translation_y(
-                        (paddle_y + scaled_movement)
-                        .min(ARENA_HEIGHT - PADDLE_HEIGHT * 0.5)
-                        .max(PADDLE_HEIGHT * 0.5));
-                }
-            }
+    fn run(&mut self, (paddles, mut controlled, input, time, sim, mut inputs) : Self::SystemData) {
+        let keys = Keys(&input);
+        for (paddle, controlled) in (&paddles, &mut controlled).join() {
+            let observation = Observation::new(&sim, paddle.side, time.delta_seconds(), &keys);
+            inputs.set(paddle.side, controlled.controller.movement(&observation));
         }
     }
-}
\ No newline at end of file
+}
+
+// the bound keys, sticks and buttons, for the controllers that read them
+struct Keys<'a>(&'a InputHandler<StringBindings>);
+
+impl<'a> AxisInput for Keys<'a> {
+    fn axis_value(&self, axis : &str) -> Option<f32> {
+        self.0.axis_value(axis)
+    }
+}
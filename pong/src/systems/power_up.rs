@@ -0,0 +1,68 @@
+use amethyst::core::math::Vector3;
+use amethyst::core::Transform;
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::{Entities, Join, ReadExpect, System, SystemData, WriteStorage};
+use amethyst::renderer::palette::Srgba;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use crate::config::PowerUpKind;
+use crate::play::{PongSprites, PowerUp, BALL_SPRITE_SIZE};
+use crate::sim::PongSim;
+
+// shows the sim's pickups as tinted balls, spawning and despawning
+// entities as pickups appear and get collected
+#[derive(SystemDesc)]
+pub struct PowerUpSystem;
+
+impl<'s> System<'s> for PowerUpSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, PowerUp>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        ReadExpect<'s, PongSim>,
+        ReadExpect<'s, PongSprites>
+    );
+
+    fn run(&mut self, (entities, mut power_ups, mut transforms, mut sprites, mut tints, sim, pong_sprites) : Self::SystemData) {
+        let mut shown = Vec::new();
+        for (entity, power_up) in (&entities, &power_ups).join() {
+            if sim.pickups().iter().any(|pickup| pickup.id == power_up.id) {
+                shown.push(power_up.id);
+            } else {
+                entities.delete(entity).expect("power-up entity is alive");
+            }
+        }
+
+        for pickup in sim.pickups().iter().filter(|pickup| !shown.contains(&pickup.id)) {
+            let scale = pickup.radius * 2.0 / BALL_SPRITE_SIZE;
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(pickup.position[0], pickup.position[1], 0.0);
+            transform.set_scale(Vector3::new(scale, scale, 1.0));
+
+            entities
+                .build_entity()
+                .with(PowerUp { id : pickup.id }, &mut power_ups)
+                .with(transform, &mut transforms)
+                .with(SpriteRender {
+                    sprite_sheet : pong_sprites.sheet.clone(),
+                    sprite_number : 1
+                }, &mut sprites)
+                .with(Tint(colour(pickup.kind)), &mut tints)
+                .build();
+        }
+    }
+}
+
+fn colour(kind : PowerUpKind) -> Srgba {
+    match kind {
+        PowerUpKind::Grow => Srgba::new(0.2, 0.9, 0.2, 1.0),
+        PowerUpKind::Shrink => Srgba::new(0.9, 0.2, 0.2, 1.0),
+        PowerUpKind::SlowBall => Srgba::new(0.2, 0.6, 1.0, 1.0),
+        PowerUpKind::Sticky => Srgba::new(1.0, 0.8, 0.2, 1.0),
+        PowerUpKind::Shield => Srgba::new(0.8, 0.8, 1.0, 1.0),
+        PowerUpKind::ReverseControls => Srgba::new(0.8, 0.2, 0.9, 1.0)
+    }
+}
@@ -0,0 +1,73 @@
+
+use amethyst::core::timing::Time;
+use amethyst::ecs::{Read, System, Write, WriteExpect};
+
+use crate::net::NetSession;
+use crate::play::Interpolation;
+use crate::replay::{Replay, ReplayPlayer};
+use crate::sim::{Inputs, PongSim};
+
+// a long hitch is dropped rather than caught up on, so a slow machine
+// slows the game down instead of spiralling into ever longer frames
+const MAX_STEPS_PER_FRAME : u32 = 8;
+
+// steps the sim at the fixed tick_rate from its config, however long the
+// frame took, and leaves the leftover fraction of a step for drawing;
+// online, the steps go through the NetSession so the peers stay in lockstep,
+// and watching a replay they take the recorded inputs at the playback speed
+#[derive(Default)]
+pub struct SimulationSystem {
+    accumulator : f32
+}
+
+impl<'s> System<'s> for SimulationSystem {
+    type SystemData = (
+        WriteExpect<'s, PongSim>,
+        Read<'s, Inputs>,
+        Read<'s, Time>,
+        Write<'s, Interpolation>,
+        Option<Write<'s, NetSession>>,
+        Option<Write<'s, ReplayPlayer>>,
+        Option<Write<'s, Replay>>
+    );
+
+    fn run(&mut self, (mut sim, inputs, time, mut interpolation, mut net, mut player, mut recording) : Self::SystemData) {
+        let step = 1.0 / sim.config().tick_rate;
+        let speed = player.as_ref().map_or(1.0, |player| player.speed());
+        self.accumulator += time.delta_seconds() * speed;
+        let max_steps = MAX_STEPS_PER_FRAME * speed.ceil().max(1.0) as u32;
+
+        if let Some(net) = net.as_mut() {
+            net.receive();
+        }
+
+        let mut steps = 0;
+        while self.accumulator >= step && steps < max_steps {
+            if let Some(net) = net.as_mut() {
+                let local_input = inputs.get(net.local_side());
+                if !net.advance(&mut sim, local_input) {
+                    break; // too far ahead of the other peer, wait for its inputs
+                }
+            } else if let Some(player) = player.as_mut() {
+                match player.next_inputs() {
+                    Some(recorded) => sim.step(&recorded, step),
+                    None => break // the recording ran out
+                }
+            } else {
+                sim.step(&inputs, step);
+                if let Some(recording) = recording.as_mut() {
+                    recording.push(&inputs);
+                }
+            }
+            self.accumulator -= step;
+            steps += 1;
+        }
+        self.accumulator = self.accumulator.min(step);
+
+        if let Some(net) = net.as_mut() {
+            net.send();
+        }
+
+        interpolation.alpha = self.accumulator / step;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, Source},
+    ecs::prelude::{Read, ReadExpect, System, SystemData, World},
+    shrev::{EventChannel, ReaderId}
+};
+
+use crate::audio::{play, Sounds};
+use crate::config::AudioConfig;
+use crate::sim::PongEvent;
+
+// plays a sound for each gameplay event
+#[derive(Default)]
+pub struct SoundSystem {
+    reader : Option<ReaderId<PongEvent>>
+}
+
+impl<'s> System<'s> for SoundSystem {
+    type SystemData = (
+        Read<'s, AssetStorage<Source>>,
+        Option<ReadExpect<'s, Sounds>>,
+        Option<Read<'s, Output>>,
+        Read<'s, AudioConfig>,
+        Read<'s, EventChannel<PongEvent>>
+    );
+
+    fn setup(&mut self, world : &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(world.fetch_mut::<EventChannel<PongEvent>>().register_reader());
+    }
+
+    fn run(&mut self, (storage, sounds, output, config, events) : Self::SystemData) {
+        let output = output.as_deref();
+
+        for event in events.read(self.reader.as_mut().expect("SoundSystem was set up")) {
+            let sounds = match sounds.as_ref() {
+                Some(sounds) => sounds,
+                None => continue
+            };
+            let sound = match event {
+                PongEvent::PaddleHit { .. } | PongEvent::PaddleGraze { .. } => &sounds.bounce,
+                PongEvent::WallHit => &sounds.wall,
+                PongEvent::BumperHit => &sounds.bounce,
+                PongEvent::Goal { .. } => &sounds.score,
+                PongEvent::MatchOver { .. } => &sounds.match_over,
+                PongEvent::Serve | PongEvent::Eliminated { .. } => continue
+            };
+            play(sound, &storage, output, config.effects_gain());
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use amethyst::{
+    ecs::prelude::{Read, System, SystemData, World, Write},
+    shrev::{EventChannel, ReaderId}
+};
+
+use crate::sim::PongEvent;
+use crate::stats::MatchStats;
+
+// counts the hits and rallies of the match into MatchStats
+#[derive(Default)]
+pub struct StatsSystem {
+    reader : Option<ReaderId<PongEvent>>
+}
+
+impl<'s> System<'s> for StatsSystem {
+    type SystemData = (
+        Write<'s, MatchStats>,
+        Read<'s, EventChannel<PongEvent>>
+    );
+
+    fn setup(&mut self, world : &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(world.fetch_mut::<EventChannel<PongEvent>>().register_reader());
+    }
+
+    fn run(&mut self, (mut stats, events) : Self::SystemData) {
+        for event in events.read(self.reader.as_mut().expect("StatsSystem was set up")) {
+            stats.record(event);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+
+use amethyst::core::math::Vector3;
+use amethyst::core::Transform;
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::{Entities, Join, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage};
+use amethyst::renderer::{Hidden, SpriteRender};
+
+use crate::play::{Ball, Interpolation, Paddle, PongSprites, BALL_SPRITE_SIZE, PADDLE_SPRITE_SIZE};
+use crate::sim::PongSim;
+
+// copies the sim state onto the rendered entities, spawning and
+// despawning ball entities as balls come and go in the sim; positions are
+// blended between the last two sim steps so motion stays smooth at any fps
+#[derive(SystemDesc)]
+pub struct SyncSystem;
+
+impl<'s> System<'s> for SyncSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Paddle>,
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Hidden>,
+        ReadExpect<'s, PongSim>,
+        ReadExpect<'s, PongSprites>,
+        Read<'s, Interpolation>
+    );
+
+    fn run(&mut self, (entities, paddles, mut balls, mut transforms, mut sprites, mut hidden, sim, pong_sprites, interpolation) : Self::SystemData) {
+        let alpha = interpolation.alpha;
+
+        // a paddle knocked out of a four-player match is hidden until the rematch
+        for (entity, paddle, transform) in (&entities, &paddles, &mut transforms).join() {
+            if let Some(state) = sim.paddle(paddle.side) {
+                hidden.remove(entity);
+                let [x, y] = state.interpolated_position(alpha);
+                transform.set_translation_x(x);
+                transform.set_translation_y(y);
+                transform.set_scale(Vector3::new(
+                    state.size[0] / PADDLE_SPRITE_SIZE[0],
+                    state.size[1] / PADDLE_SPRITE_SIZE[1],
+                    1.0
+                ));
+            } else {
+                hidden.insert(entity, Hidden).expect("paddle entity is alive");
+            }
+        }
+
+        let mut shown = Vec::new();
+        for (entity, ball, transform) in (&entities, &balls, &mut transforms).join() {
+            match sim.ball(ball.id) {
+                Some(state) => {
+                    let [x, y] = state.interpolated_position(alpha);
+                    transform.set_translation_x(x);
+                    transform.set_translation_y(y);
+                    shown.push(ball.id);
+                },
+                None => {
+                    entities.delete(entity).expect("ball entity is alive");
+                }
+            }
+        }
+
+        for state in sim.balls().iter().filter(|state| !shown.contains(&state.id)) {
+            let [x, y] = state.interpolated_position(alpha);
+            let scale = state.radius * 2.0 / BALL_SPRITE_SIZE;
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(x, y, 0.0);
+            transform.set_scale(Vector3::new(scale, scale, 1.0));
+
+            entities
+                .build_entity()
+                .with(Ball { id : state.id }, &mut balls)
+                .with(transform, &mut transforms)
+                .with(SpriteRender {
+                    sprite_sheet : pong_sprites.sheet.clone(),
+                    sprite_number : 1
+                }, &mut sprites)
+                .build();
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use amethyst::ecs::{Entities, Entity, Join, Read, ReadExpect, System, WriteStorage};
+use amethyst::renderer::Camera;
+use amethyst::ui::{UiText, UiTransform};
+use amethyst::window::ScreenDimensions;
+
+use crate::config::PongConfig;
+use crate::play::{score_layout, ScoreText, LAYOUT_SIZE};
+
+// fits the whole arena in the window at any size without stretching it, the
+// room left over goes to bars on either side (pillarboxing) or above and
+// below (letterboxing); the score texts are moved and scaled to stay where
+// they are on the arena. Runs in the main dispatcher, so a window resized
+// while paused or over the game over screen is fitted as well
+#[derive(Default)]
+pub struct ViewportSystem {
+    fitted : Option<(Entity, [f32; 2])> // camera and the window size it was fitted to
+}
+
+impl<'s> System<'s> for ViewportSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Camera>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        ReadExpect<'s, ScreenDimensions>,
+        ReadExpect<'s, PongConfig>,
+        Option<Read<'s, ScoreText>>
+    );
+
+    fn run(&mut self, (entities, mut cameras, mut ui_transforms, mut ui_text, screen, config, score_text) : Self::SystemData) {
+        let window = [screen.width(), screen.height()];
+        if window[0] <= 0.0 || window[1] <= 0.0 {
+            return; // minimized
+        }
+        let arena = [config.arena.width, config.arena.height];
+
+        // the arena in pixels, as big as fits, and the margins around it
+        let scale = (window[0] / arena[0]).min(window[1] / arena[1]);
+        let shown = [arena[0] * scale, arena[1] * scale];
+        let margin = [(window[0] - shown[0]) * 0.5, (window[1] - shown[1]) * 0.5];
+
+        for (entity, camera) in (&entities, &mut cameras).join() {
+            if self.fitted == Some((entity, window)) {
+                continue;
+            }
+            // arena units across the whole window, centered on the arena like the camera
+            let view = [window[0] / scale, window[1] / scale];
+            *camera = Camera::orthographic(-view[0] * 0.5, view[0] * 0.5, -view[1] * 0.5, view[1] * 0.5, 0.125, 2000.0);
+            self.fitted = Some((entity, window));
+        }
+
+        let texts = match score_text.as_ref() {
+            Some(score_text) => &score_text.texts,
+            None => return
+        };
+        let layout_scale = shown[0].min(shown[1]) / LAYOUT_SIZE;
+        for (side, entity) in texts.iter() {
+            let (anchor, offset, size, font_size) = score_layout(texts.len(), *side);
+
+            // anchored to the window edge, pushed in by the bar on that edge
+            let (edge_x, edge_y) = anchor.norm_offset();
+            let x = offset[0] * layout_scale - edge_x * 2.0 * margin[0];
+            let y = offset[1] * layout_scale - edge_y * 2.0 * margin[1];
+
+            if let Some(transform) = ui_transforms.get_mut(*entity) {
+                if transform.local_x != x || transform.local_y != y || transform.width != size[0] * layout_scale {
+                    transform.local_x = x;
+                    transform.local_y = y;
+                    transform.width = size[0] * layout_scale;
+                    transform.height = size[1] * layout_scale;
+                }
+            }
+            if let Some(text) = ui_text.get_mut(*entity) {
+                if text.font_size != font_size * layout_scale {
+                    text.font_size = font_size * layout_scale;
+                }
+            }
+        }
+    }
+}
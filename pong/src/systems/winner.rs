@@ -1,54 +1,50 @@
 use amethyst::{
-    core::transform::Transform,
-    ecs::prelude::{System, Join, WriteStorage, Write, ReadExpect},
+    ecs::prelude::{System, WriteStorage, Write, ReadExpect},
     ui::UiText
 };
 
-use crate::pong::{Ball, ARENA_WIDTH, ScoreBoard, ScoreText};
+use crate::play::{Rally, ScoreBoard, ScoreText};
+use crate::sim::{PongSim, SIDES};
+use crate::stats::MatchStats;
 
+// mirrors the sim scores and rally into the ScoreBoard, Rally and the score
+// texts, which show the points with two players and the lives left with
+// four; goals and sets are announced by BannerSystem. Also keeps the
+// ball speed and possession in MatchStats up with the sim
 pub struct WinnerSystem;
 
 impl<'s> System<'s> for WinnerSystem {
     type SystemData = (
-        WriteStorage<'s, Ball>,
-        WriteStorage<'s, Transform>,
-        WriteStorage<'s, UiText>, 
-        Write<'s, ScoreBoard>, 
-        ReadExpect<'s, ScoreText>
+        WriteStorage<'s, UiText>,
+        Write<'s, ScoreBoard>,
+        Write<'s, Rally>,
+        Write<'s, MatchStats>,
+        ReadExpect<'s, ScoreText>,
+        ReadExpect<'s, PongSim>
     );
 
-    fn run(&mut self, (mut balls, mut transform, mut ui_text, mut scores, score_text) : Self::SystemData) {
-        for (ball, transform) in (&mut balls, &mut transform).join() {
-            let ball_x = transform.translation().x;
+    fn run(&mut self, (mut ui_text, mut scores, mut rally, mut stats, score_text, sim) : Self::SystemData) {
+        rally.hits = sim.rally();
+        rally.speed = sim.ball_speed();
+        stats.sample(&sim);
 
-            let did_hit = if ball_x <= ball.radius {
-                scores.score_right = (scores.score_right +1).min(999);
-
-                if let Some(text) = ui_text.get_mut(score_text.p2_score) {
-                    text.text = scores.score_right.to_string();
-                }
+        for side in SIDES.iter() {
+            let index = side.index();
+            scores.scores[index] = sim.score(*side);
+            scores.sets[index] = sim.sets(*side);
+            scores.lives[index] = sim.lives(*side);
+        }
 
-                println!("Player 2 scores!");
-                true
-            } else if ball_x >= ARENA_WIDTH - ball.radius {
-                scores.score_left = (scores.score_left +1).min(999);
+        let four_player = sim.config().four_player.enabled;
+        for (side, entity) in score_text.texts.iter() {
+            let shown = if four_player { scores.lives[side.index()] } else { scores.scores[side.index()] };
+            let shown = shown.to_string();
 
-                if let Some(text) = ui_text.get_mut(score_text.p1_score) {
-                    text.text = scores.score_left.to_string();
+            if let Some(text) = ui_text.get_mut(*entity) {
+                if text.text != shown {
+                    text.text = shown;
                 }
-                
-                println!("Player 1 scores");
-                true
-            } else {
-                false
-            };
-
-            if did_hit {
-                ball.velocity[0] = -ball.velocity[0];
-                transform.set_translation_x(ARENA_WIDTH / 2.0);
             }
         }
     }
-
-
-}
\ No newline at end of file
+}